@@ -1,16 +1,24 @@
-use bevy::prelude::*;
+use bevy::{input::InputSystem, prelude::*};
 use bevy_simple_subsecond_system::hot;
 
+use crate::{
+    grid::Grid,
+    snap::{SNAP_APERTURE, SnapKind, SnapTargets, world_per_pixel},
+};
+
 #[derive(Resource, Default)]
 pub struct Cursor {
     pub position: Vec3,
+    /// Kind of point the position snapped onto, if any.
+    pub snap: Option<SnapKind>,
 }
 pub struct CursorPlugin;
 
 impl Plugin for CursorPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(Cursor::default())
-            .add_systems(Update, update_cursor); //, draw_cursor));
+            // Settle the cursor before any tool reads it this frame
+            .add_systems(PreUpdate, update_cursor.after(InputSystem)); //, draw_cursor));
     }
 }
 
@@ -19,6 +27,8 @@ fn update_cursor(
     camera_query: Single<(&Camera, &GlobalTransform)>,
     windows: Query<&Window>,
     mut cursor: ResMut<Cursor>,
+    targets: SnapTargets,
+    grid: Res<Grid>,
 ) {
     let Ok(windows) = windows.single() else {
         return;
@@ -39,9 +49,22 @@ fn update_cursor(
     let Some(distance) = ray.intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Dir3::Y)) else {
         return;
     };
-    cursor.position = ray.get_point(distance);
+    let position = ray.get_point(distance);
+
+    // Snap onto nearby points of shapes so new ones connect exactly, or else onto the grid
+    let aperture = world_per_pixel(camera, camera_transform, cursor_position)
+        .map_or(0., |pixel| pixel * SNAP_APERTURE);
+    let snapped = targets.snap(position, aperture);
+    cursor.snap = snapped.map(|(_, kind)| kind);
+    cursor.position = match snapped {
+        Some((point, _)) => point,
+        None if grid.snap => grid.snapped(position),
+        None => position,
+    };
 }
 
+// Left out of the plugin, kept for debugging the cursor position
+#[allow(dead_code)]
 #[hot]
 fn draw_cursor(mut gizmos: Gizmos, cursor: Res<Cursor>) {
     gizmos.circle(
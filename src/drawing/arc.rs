@@ -1,3 +1,5 @@
+use std::f32::consts::TAU;
+
 use bevy::prelude::*;
 use bevy_simple_subsecond_system::*;
 
@@ -7,22 +9,101 @@ use crate::{
 };
 
 use super::{
+    construction::Construction,
     dot::Dot,
     draw::{CurrentDrawing, DEFAULT_POS, DEFAULT_RESOLUTION, DrawMode, reset_current_drawing},
+    geometry::{circle_through, plane_angle, signed_angle},
 };
 
-#[derive(Component, Debug, Default)]
+/// Arc from `start` to `end` around `center`.
+/// `sweep` is the signed angle in radians around +Y, so it carries both direction and size.
+#[derive(Component, Debug, Default, Clone, Copy)]
 pub struct Arc {
     pub center: Vec3,
     pub start: Vec3,
     pub end: Vec3,
+    pub sweep: f32,
+}
+
+impl Arc {
+    pub fn new(center: Vec3, start: Vec3, sweep: f32) -> Self {
+        Arc {
+            center,
+            start,
+            end: center + Quat::from_rotation_y(sweep) * (start - center),
+            sweep,
+        }
+    }
+
+    pub fn radius(&self) -> f32 {
+        (self.start - self.center).length()
+    }
+
+    /// Distance along the arc from `start` to `end`.
+    pub fn length(&self) -> f32 {
+        self.radius() * self.sweep.abs()
+    }
+
+    /// Whether the direction from the center to `point` falls within the sweep.
+    pub fn contains_angle(&self, point: Vec3) -> bool {
+        let angle = plane_angle(point - self.center) - plane_angle(self.start - self.center);
+        if self.sweep >= 0. {
+            angle.rem_euclid(TAU) <= self.sweep
+        } else {
+            (-angle).rem_euclid(TAU) <= -self.sweep
+        }
+    }
+
+    /// Arc leaving `start` along `direction` and ending at `end`.
+    /// Returns `None` when `end` lies straight ahead, where the arc would be a line.
+    pub fn tangent(start: Vec3, direction: Vec3, end: Vec3) -> Option<Self> {
+        let normal = Vec3::Y.cross(direction).normalize_or_zero();
+        let offset = end - start;
+        let along_normal = normal.dot(offset);
+        if along_normal.abs() < f32::EPSILON {
+            return None;
+        }
+        let center = start + normal * offset.length_squared() / (2. * along_normal);
+        let sweep = (plane_angle(end - center) - plane_angle(start - center)).rem_euclid(TAU);
+        // Positive sweeps leave `start` along Y x (start - center)
+        let sweep = if Vec3::Y.cross(start - center).dot(direction) > 0. {
+            sweep
+        } else {
+            sweep - TAU
+        };
+        Some(Arc::new(center, start, sweep))
+    }
+
+    /// Direction of travel where the arc arrives at `end`.
+    pub fn end_direction(&self) -> Vec3 {
+        Vec3::Y.cross(self.end - self.center).normalize_or_zero() * self.sweep.signum()
+    }
+
+    /// Points along the arc, spaced like a `DEFAULT_RESOLUTION` circle.
+    pub fn positions(&self) -> Vec<Vec3> {
+        let steps = ((DEFAULT_RESOLUTION as f32 * self.sweep.abs() / TAU).ceil() as u32).max(1);
+        (0..=steps)
+            .map(|step| {
+                self.center
+                    + Quat::from_rotation_y(self.sweep * step as f32 / steps as f32)
+                        * (self.start - self.center)
+            })
+            .collect()
+    }
 }
 
 pub struct ArcPlugin;
 
 impl Plugin for ArcPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, display_arcs);
+        app.add_systems(
+            Update,
+            (
+                handle_draw_arc.run_if(in_state(DrawMode::Arc)),
+                handle_draw_three_point_arc.run_if(in_state(DrawMode::ThreePointArc)),
+            ),
+        )
+        .add_systems(Update, display_arcs);
     }
 }
 
@@ -30,6 +111,7 @@ impl Plugin for ArcPlugin {
 pub fn handle_draw_arc(
     mut commands: Commands,
     mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
     cursor: Res<Cursor>,
     mut current_drawing: ResMut<CurrentDrawing>,
 ) {
@@ -54,10 +136,7 @@ pub fn handle_draw_arc(
         current_drawing.position[2] = cursor.position;
     }
 
-    for position in current_drawing.position {
-        println!("{:?}", position);
-    }
-    println!("");
+    debug!("Arc points: {:?}", current_drawing.position);
 
     let center = current_drawing.position[0];
     let start = current_drawing.position[1];
@@ -72,11 +151,11 @@ pub fn handle_draw_arc(
             },
         ));
         commands.spawn((
-            Arc {
-                center: center,
-                start: start,
-                end: end,
-            },
+            Arc::new(
+                center,
+                start,
+                arc_sweep(center, start, end, keyboard.pressed(KeyCode::ShiftLeft)),
+            ),
             Reloadable {
                 level: ReloadLevel::Hard,
             },
@@ -85,17 +164,99 @@ pub fn handle_draw_arc(
     }
 }
 
+#[hot]
+pub fn handle_draw_three_point_arc(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+) {
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Define start of arc
+    if current_drawing.position[0] == DEFAULT_POS {
+        current_drawing.position[0] = cursor.position;
+    }
+    // Define point the arc passes through
+    else if current_drawing.position[1] == DEFAULT_POS {
+        current_drawing.position[1] = cursor.position;
+    }
+    // Define end of arc
+    else if current_drawing.position[2] == DEFAULT_POS {
+        current_drawing.position[2] = cursor.position;
+    }
+
+    let start = current_drawing.position[0];
+    let through = current_drawing.position[1];
+    let end = current_drawing.position[2];
+
+    if start == DEFAULT_POS || through == DEFAULT_POS || end == DEFAULT_POS {
+        return;
+    }
+    // Collinear points have no circle through them, so wait for another end
+    let Some(center) = circle_through(start, through, end) else {
+        current_drawing.position[2] = DEFAULT_POS;
+        return;
+    };
+
+    commands.spawn((
+        Dot { position: center },
+        Reloadable {
+            level: ReloadLevel::Hard,
+        },
+    ));
+    commands.spawn((
+        Arc::new(
+            center,
+            start,
+            three_point_sweep(center, start, through, end),
+        ),
+        Reloadable {
+            level: ReloadLevel::Hard,
+        },
+    ));
+    reset_current_drawing(current_drawing);
+}
+
 #[hot]
 pub fn display_arcs(
     mut gizmos: Gizmos,
-    query: Query<&Arc>,
+    query: Query<&Arc, Without<Construction>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
     cursor: Res<Cursor>,
     state: Res<State<DrawMode>>,
     current_drawing: ResMut<CurrentDrawing>,
 ) {
-    // Display existing circles
+    // Display existing arcs
     for arc in query.iter() {
-        gizmos.short_arc_3d_between(arc.center, arc.start, arc.end, Color::WHITE);
+        gizmos.linestrip(arc.positions(), Color::WHITE);
+    }
+    // Display currently drawn three point arc
+    if state.get() == &DrawMode::ThreePointArc {
+        let start = current_drawing.position[0];
+        let through = current_drawing.position[1];
+
+        if start != DEFAULT_POS && through == DEFAULT_POS {
+            gizmos.line(start, cursor.position, Color::WHITE);
+        }
+        if start != DEFAULT_POS && through != DEFAULT_POS {
+            match circle_through(start, through, cursor.position) {
+                Some(center) => {
+                    let sweep = three_point_sweep(center, start, through, cursor.position);
+                    gizmos.linestrip(Arc::new(center, start, sweep).positions(), Color::WHITE);
+                }
+                None => {
+                    gizmos.line(start, cursor.position, Color::WHITE);
+                }
+            }
+        }
+        return;
     }
     // Display currently drawn circle
     if state.get() != &DrawMode::Arc {
@@ -131,6 +292,85 @@ pub fn display_arcs(
         gizmos.line(center, to, Color::WHITE);
     }
     if start != DEFAULT_POS {
-        gizmos.line(center, cursor.position, Color::WHITE);
+        let arc = Arc::new(
+            center,
+            start,
+            arc_sweep(
+                center,
+                start,
+                cursor.position,
+                keyboard.pressed(KeyCode::ShiftLeft),
+            ),
+        );
+        gizmos.line(center, arc.end, Color::WHITE);
+        gizmos.linestrip(arc.positions(), Color::WHITE);
+    }
+}
+
+/// Takes the short way from `start` to `end`, or the long way around when `flip` is held.
+fn arc_sweep(center: Vec3, start: Vec3, end: Vec3, flip: bool) -> f32 {
+    let sweep = signed_angle(start - center, end - center);
+    if flip {
+        sweep - TAU.copysign(sweep)
+    } else {
+        sweep
+    }
+}
+
+/// Sweeps from `start` to `end` in whichever direction passes through `through`.
+fn three_point_sweep(center: Vec3, start: Vec3, through: Vec3, end: Vec3) -> f32 {
+    let start_angle = plane_angle(start - center);
+    let sweep = (plane_angle(end - center) - start_angle).rem_euclid(TAU);
+    let through_angle = (plane_angle(through - center) - start_angle).rem_euclid(TAU);
+    if through_angle < sweep {
+        sweep
+    } else {
+        sweep - TAU
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use super::*;
+
+    #[test]
+    fn sweep_sign_picks_direction() {
+        let left = Arc::new(Vec3::ZERO, Vec3::X, FRAC_PI_2);
+        let right = Arc::new(Vec3::ZERO, Vec3::X, -FRAC_PI_2);
+        assert!(left.end.distance(Vec3::NEG_Z) < 1e-5);
+        assert!(right.end.distance(Vec3::Z) < 1e-5);
+    }
+
+    #[test]
+    fn positions_follow_the_sweep() {
+        let arc = Arc::new(Vec3::ZERO, Vec3::X, -3. * FRAC_PI_2);
+        let positions = arc.positions();
+        assert_eq!(positions.len(), DEFAULT_RESOLUTION as usize * 3 / 4 + 1);
+        assert_eq!(positions[0], arc.start);
+        assert!(positions.last().unwrap().distance(arc.end) < 1e-5);
+        assert!(positions.iter().all(|p| (p.length() - 1.).abs() < 1e-5));
+        // The long way round from +X to -Z passes through +Z and -X
+        assert!(positions.iter().any(|p| p.distance(Vec3::NEG_X) < 1e-5));
+    }
+
+    #[test]
+    fn flip_takes_the_long_way() {
+        assert!((arc_sweep(Vec3::ZERO, Vec3::X, Vec3::NEG_Z, false) - FRAC_PI_2).abs() < 1e-5);
+        assert!((arc_sweep(Vec3::ZERO, Vec3::X, Vec3::NEG_Z, true) + 3. * FRAC_PI_2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn three_point_sweep_passes_through() {
+        let long_way = three_point_sweep(Vec3::ZERO, Vec3::X, Vec3::NEG_X, Vec3::NEG_Z);
+        assert!((long_way + 3. * FRAC_PI_2).abs() < 1e-5);
+        let short_way = three_point_sweep(
+            Vec3::ZERO,
+            Vec3::X,
+            Quat::from_rotation_y(PI / 4.) * Vec3::X,
+            Vec3::NEG_Z,
+        );
+        assert!((short_way - FRAC_PI_2).abs() < 1e-5);
     }
 }
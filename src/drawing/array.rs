@@ -0,0 +1,272 @@
+use std::f32::consts::{PI, TAU};
+
+use bevy::prelude::*;
+use bevy_simple_subsecond_system::*;
+
+use crate::cursor::Cursor;
+
+use super::{
+    arc::Arc,
+    draw::{CurrentDrawing, DEFAULT_POS, DrawMode, reset_current_drawing},
+    line::Line,
+    offset::Segment,
+    pick::{pick_arc, pick_line},
+    select::SelectedShapes,
+    size::DOT_RADIUS,
+};
+
+pub const MAX_ARRAY_COUNT: u32 = 100;
+
+/// Sizes of the three arrays: a `rows` by `columns` grid, and `count` copies around a
+/// center through `angle` or along a path, originals included.
+#[derive(Resource, Debug, PartialEq)]
+pub struct ArrayOptions {
+    pub rows: u32,
+    pub columns: u32,
+    pub count: u32,
+    pub angle: f32,
+}
+
+impl Default for ArrayOptions {
+    fn default() -> Self {
+        ArrayOptions {
+            rows: 2,
+            columns: 3,
+            count: 6,
+            angle: TAU,
+        }
+    }
+}
+
+/// Paths to copy along, and the selection; both read lines and arcs.
+type PathShapes<'w, 's> = ParamSet<
+    'w,
+    's,
+    (
+        Query<'static, 'static, (Entity, &'static Line)>,
+        Query<'static, 'static, (Entity, &'static Arc)>,
+        SelectedShapes<'static, 'static>,
+    ),
+>;
+
+pub struct ArrayPlugin;
+
+impl Plugin for ArrayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ArrayOptions::default())
+            .add_systems(
+                Update,
+                (
+                    change_array_options,
+                    handle_draw_rectangular_array.run_if(in_state(DrawMode::RectangularArray)),
+                    handle_draw_polar_array.run_if(in_state(DrawMode::PolarArray)),
+                    handle_draw_path_array.run_if(in_state(DrawMode::PathArray)),
+                )
+                    .chain(),
+            )
+            .add_systems(Update, display_array_preview);
+    }
+}
+
+#[hot]
+fn change_array_options(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    state: Res<State<DrawMode>>,
+    mut options: ResMut<ArrayOptions>,
+) {
+    let more = keyboard.any_just_pressed([KeyCode::Equal, KeyCode::NumpadAdd]);
+    let fewer = keyboard.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]);
+    let up = keyboard.just_pressed(KeyCode::BracketRight);
+    let down = keyboard.just_pressed(KeyCode::BracketLeft);
+    if !(more || fewer || up || down) {
+        return;
+    }
+    let step = |value: u32| {
+        if more || up {
+            (value + 1).min(MAX_ARRAY_COUNT)
+        } else {
+            (value - 1).max(1)
+        }
+    };
+
+    match state.get() {
+        // +/- changes columns and [/] changes rows
+        DrawMode::RectangularArray if more || fewer => options.columns = step(options.columns),
+        DrawMode::RectangularArray => options.rows = step(options.rows),
+        // [/] changes the angle the copies spread over
+        DrawMode::PolarArray if up => options.angle = (options.angle + PI / 12.).min(TAU),
+        DrawMode::PolarArray if down => options.angle = (options.angle - PI / 12.).max(PI / 12.),
+        DrawMode::PolarArray | DrawMode::PathArray => {
+            options.count = step(options.count).max(2);
+        }
+        _ => return,
+    }
+    info!("Array: {:?}", *options);
+}
+
+#[hot]
+fn handle_draw_rectangular_array(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+    options: Res<ArrayOptions>,
+    mut selection: SelectedShapes,
+) {
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    // Shift-clicks pick the selection instead
+    if !mouse_input.just_pressed(MouseButton::Left) || keyboard.pressed(KeyCode::ShiftLeft) {
+        return;
+    }
+    if selection.is_empty() {
+        warn!("Shift-click shapes to array first");
+        return;
+    }
+
+    // Define base point
+    if current_drawing.position[0] == DEFAULT_POS {
+        current_drawing.position[0] = cursor.position;
+        return;
+    }
+
+    // Copy across the grid once the spacing is defined
+    let spacing = cursor.position - current_drawing.position[0];
+    for offset in grid_offsets(spacing, options.rows, options.columns).skip(1) {
+        selection.copy(|point| point + offset, false);
+    }
+    selection.clear();
+    reset_current_drawing(current_drawing);
+}
+
+#[hot]
+fn handle_draw_polar_array(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    cursor: Res<Cursor>,
+    options: Res<ArrayOptions>,
+    mut selection: SelectedShapes,
+) {
+    // Shift-clicks pick the selection instead
+    if !mouse_input.just_pressed(MouseButton::Left) || keyboard.pressed(KeyCode::ShiftLeft) {
+        return;
+    }
+    if selection.is_empty() {
+        warn!("Shift-click shapes to array first");
+        return;
+    }
+
+    // Copy around the clicked center
+    let center = cursor.position;
+    for angle in polar_angles(options.count, options.angle).skip(1) {
+        selection.copy(
+            |point| center + Quat::from_rotation_y(angle) * (point - center),
+            false,
+        );
+    }
+    selection.clear();
+}
+
+#[hot]
+fn handle_draw_path_array(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    cursor: Res<Cursor>,
+    options: Res<ArrayOptions>,
+    mut shapes: PathShapes,
+) {
+    // Shift-clicks pick the selection instead
+    if !mouse_input.just_pressed(MouseButton::Left) || keyboard.pressed(KeyCode::ShiftLeft) {
+        return;
+    }
+    if shapes.p2().is_empty() {
+        warn!("Shift-click shapes to array first");
+        return;
+    }
+
+    // Copy along the clicked path, spacing the copies evenly from its start
+    let line = pick_line(shapes.p0().iter(), cursor.position).map(|(_, line)| Segment::Line(*line));
+    let Some(path) = line.or_else(|| {
+        pick_arc(shapes.p1().iter(), cursor.position).map(|(_, arc)| Segment::Arc(*arc))
+    }) else {
+        return;
+    };
+    let mut selection = shapes.p2();
+    for offset in path_offsets(&path, options.count).skip(1) {
+        selection.copy(|point| point + offset, false);
+    }
+    selection.clear();
+}
+
+#[hot]
+fn display_array_preview(
+    mut gizmos: Gizmos,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    current_drawing: Res<CurrentDrawing>,
+    options: Res<ArrayOptions>,
+    lines: Query<(Entity, &Line)>,
+    arcs: Query<(Entity, &Arc)>,
+) {
+    // Mark where each copy's base point would land
+    let (base, offsets): (Vec3, Vec<Vec3>) = match state.get() {
+        DrawMode::RectangularArray if current_drawing.position[0] != DEFAULT_POS => {
+            let base = current_drawing.position[0];
+            let spacing = cursor.position - base;
+            (
+                base,
+                grid_offsets(spacing, options.rows, options.columns).collect(),
+            )
+        }
+        DrawMode::PolarArray => {
+            let start = cursor.position + Vec3::X;
+            (
+                start,
+                polar_angles(options.count, options.angle)
+                    .map(|angle| cursor.position + Quat::from_rotation_y(angle) * Vec3::X - start)
+                    .collect(),
+            )
+        }
+        DrawMode::PathArray => {
+            let Some(path) = pick_line(lines.iter(), cursor.position)
+                .map(|(_, line)| Segment::Line(*line))
+                .or_else(|| {
+                    pick_arc(arcs.iter(), cursor.position).map(|(_, arc)| Segment::Arc(*arc))
+                })
+            else {
+                return;
+            };
+            (path.start(), path_offsets(&path, options.count).collect())
+        }
+        _ => return,
+    };
+    for offset in offsets {
+        gizmos.circle(
+            Isometry3d::new(base + offset, Quat::from_rotation_arc(Vec3::Z, Vec3::Y)),
+            DOT_RADIUS,
+            Color::WHITE,
+        );
+    }
+}
+
+/// Offsets of a `rows` by `columns` grid, with `spacing` split into its X and Z steps.
+fn grid_offsets(spacing: Vec3, rows: u32, columns: u32) -> impl Iterator<Item = Vec3> {
+    (0..rows).flat_map(move |row| {
+        (0..columns).map(move |column| vec3(spacing.x * column as f32, 0., spacing.z * row as f32))
+    })
+}
+
+/// Angles of `count` copies spread over `angle`. A full turn leaves no copy on the start.
+fn polar_angles(count: u32, angle: f32) -> impl Iterator<Item = f32> {
+    let steps = if angle >= TAU { count } else { count - 1 };
+    (0..count).map(move |index| angle * index as f32 / steps.max(1) as f32)
+}
+
+/// Offsets from the start of `path` to `count` evenly spaced points along it.
+fn path_offsets(path: &Segment, count: u32) -> impl Iterator<Item = Vec3> {
+    let path = *path;
+    (0..count)
+        .map(move |index| path.point_at(index as f32 / (count - 1).max(1) as f32) - path.start())
+}
@@ -0,0 +1,155 @@
+use bevy::{math::cubic_splines::CubicSegment, prelude::*};
+use bevy_simple_subsecond_system::*;
+
+use crate::{
+    cursor::Cursor,
+    reload::{ReloadLevel, Reloadable},
+};
+
+use super::{
+    construction::Construction,
+    dot::Dot,
+    draw::{CurrentDrawing, DEFAULT_POS, DEFAULT_RESOLUTION, DrawMode, LineChain, reset_drawing},
+};
+
+/// Cubic Bézier segment: start anchor, start handle, end handle, end anchor.
+#[derive(Component, Debug, Default)]
+pub struct Bezier {
+    pub points: [Vec3; 4],
+}
+
+impl Bezier {
+    /// Points along the curve, `DEFAULT_RESOLUTION` steps from start to end anchor.
+    pub fn positions(&self) -> Vec<Vec3> {
+        CubicSegment::new_bezier(self.points)
+            .iter_positions(DEFAULT_RESOLUTION as usize)
+            .collect()
+    }
+}
+
+pub struct BezierPlugin;
+
+impl Plugin for BezierPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            handle_draw_bezier.run_if(in_state(DrawMode::Bezier)),
+        )
+        .add_systems(Update, display_beziers);
+    }
+}
+
+#[hot]
+pub fn handle_draw_bezier(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+    mut line_chain: ResMut<LineChain>,
+) {
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_drawing(current_drawing, line_chain);
+        return;
+    }
+
+    // Define start anchor, then drag out its handle
+    if current_drawing.position[0] == DEFAULT_POS {
+        if mouse_input.just_pressed(MouseButton::Left) {
+            current_drawing.position[0] = cursor.position;
+        }
+        return;
+    }
+    if current_drawing.position[1] == DEFAULT_POS {
+        if mouse_input.just_released(MouseButton::Left) {
+            current_drawing.position[1] = cursor.position;
+        }
+        return;
+    }
+    // Define end anchor, then drag out its handle
+    if current_drawing.position[2] == DEFAULT_POS {
+        if mouse_input.just_pressed(MouseButton::Left) {
+            current_drawing.position[2] = cursor.position;
+        }
+        return;
+    }
+    if !mouse_input.just_released(MouseButton::Left) {
+        return;
+    }
+
+    let start = current_drawing.position[0];
+    let end = current_drawing.position[2];
+
+    if line_chain.count == 0 {
+        commands.spawn((
+            Dot { position: start },
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+        ));
+    }
+    commands.spawn((
+        Dot { position: end },
+        Reloadable {
+            level: ReloadLevel::Hard,
+        },
+    ));
+    commands.spawn((
+        Bezier {
+            points: bezier_points(start, current_drawing.position[1], end, cursor.position),
+        },
+        Reloadable {
+            level: ReloadLevel::Hard,
+        },
+    ));
+
+    // Continue from the end anchor, keeping the dragged handle as the next start handle
+    current_drawing.position[0] = end;
+    current_drawing.position[1] = cursor.position;
+    current_drawing.position[2] = DEFAULT_POS;
+    line_chain.count += 1;
+}
+
+#[hot]
+fn display_beziers(
+    mut gizmos: Gizmos,
+    query: Query<&Bezier, Without<Construction>>,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    current_drawing: ResMut<CurrentDrawing>,
+) {
+    // Display existing curves
+    for bezier in query.iter() {
+        gizmos.linestrip(bezier.positions(), Color::WHITE);
+    }
+    // Display currently drawn curve
+    if state.get() != &DrawMode::Bezier || current_drawing.position[0] == DEFAULT_POS {
+        return;
+    }
+
+    let start = current_drawing.position[0];
+    let start_handle = current_drawing.position[1];
+    let end = current_drawing.position[2];
+
+    if start_handle == DEFAULT_POS {
+        gizmos.line(start, cursor.position, Color::WHITE);
+        return;
+    }
+    gizmos.line(start, start_handle, Color::WHITE);
+
+    let points = if end == DEFAULT_POS {
+        [start, start_handle, cursor.position, cursor.position]
+    } else {
+        gizmos.line(2. * end - cursor.position, cursor.position, Color::WHITE);
+        bezier_points(start, start_handle, end, cursor.position)
+    };
+    gizmos.linestrip(
+        CubicSegment::new_bezier(points).iter_positions(DEFAULT_RESOLUTION as usize),
+        Color::WHITE,
+    );
+}
+
+/// The handle dragged out of the end anchor leads the next segment,
+/// so the end handle of this segment mirrors it through the anchor.
+fn bezier_points(start: Vec3, start_handle: Vec3, end: Vec3, drag: Vec3) -> [Vec3; 4] {
+    [start, start_handle, 2. * end - drag, end]
+}
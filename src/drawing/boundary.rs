@@ -0,0 +1,248 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use super::{
+    geometry::{point_in_polygon, segment_intersection},
+    size::MERGE_DISTANCE,
+};
+
+/// Smallest closed outline formed by `segments` around `point`, counter-clockwise in XZ.
+/// Segments are split wherever they cross, so overlapping shapes bound regions too.
+pub fn find_boundary(segments: &[[Vec3; 2]], point: Vec3) -> Option<Vec<Vec3>> {
+    let (vertices, neighbors) = planar_graph(segments);
+
+    // Try the edges hit by a ray from `point` towards +X, nearest first. The outside of
+    // an island is walked clockwise, so the ray carries on past it
+    let ray_end = point + Vec3::X * 1e6;
+    let mut hits: Vec<(f32, usize, usize)> = neighbors
+        .iter()
+        .enumerate()
+        .flat_map(|(a, adjacent)| adjacent.iter().map(move |&b| (a, b)))
+        .filter(|(a, b)| a < b)
+        .filter_map(|(a, b)| {
+            segment_intersection(point, ray_end, vertices[a], vertices[b]).map(|(t, _)| (t, a, b))
+        })
+        .collect();
+    hits.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    hits.into_iter().find_map(|(_, mut from, mut to)| {
+        // Walk with the region on the left
+        if cross(vertices[to] - vertices[from], point - vertices[from]) < 0. {
+            (from, to) = (to, from);
+        }
+        walk_face(&vertices, &neighbors, from, to)
+            .filter(|outline| signed_area(outline) > 0. && point_in_polygon(point, outline))
+    })
+}
+
+/// Outlines of the shapes standing free inside `outline`, clockwise in XZ.
+/// Shapes inside another island are left out, since that island already cuts them off.
+pub fn find_islands(segments: &[[Vec3; 2]], outline: &[Vec3]) -> Vec<Vec<Vec3>> {
+    let (vertices, neighbors) = planar_graph(segments);
+
+    // Group vertices into connected shapes, leaving out the one the outline belongs to
+    let mut component = vec![usize::MAX; vertices.len()];
+    let mut components: Vec<Vec<usize>> = Vec::new();
+    for start in 0..vertices.len() {
+        if component[start] != usize::MAX {
+            continue;
+        }
+        let mut members = vec![start];
+        component[start] = components.len();
+        let mut index = 0;
+        while index < members.len() {
+            for &next in neighbors[members[index]].iter() {
+                if component[next] == usize::MAX {
+                    component[next] = components.len();
+                    members.push(next);
+                }
+            }
+            index += 1;
+        }
+        components.push(members);
+    }
+    let on_outline = |vertex: Vec3| {
+        outline
+            .iter()
+            .any(|point| point.distance(vertex) < MERGE_DISTANCE)
+    };
+
+    // Shapes don't cross each other once split, so one vertex inside puts the whole shape inside
+    let islands: Vec<Vec<Vec3>> = components
+        .iter()
+        .filter(|members| {
+            members.iter().all(|&vertex| !on_outline(vertices[vertex]))
+                && point_in_polygon(vertices[members[0]], outline)
+        })
+        .filter_map(|members| {
+            // The outside of a shape is the face it encloses walking clockwise
+            members
+                .iter()
+                .flat_map(|&from| neighbors[from].iter().map(move |&to| (from, to)))
+                .filter_map(|(from, to)| walk_face(&vertices, &neighbors, from, to))
+                .min_by(|a, b| signed_area(a).total_cmp(&signed_area(b)))
+                .filter(|island| signed_area(island) < 0.)
+        })
+        .collect();
+    islands
+        .iter()
+        .enumerate()
+        .filter(|(index, island)| {
+            !islands
+                .iter()
+                .enumerate()
+                .any(|(other, around)| other != *index && point_in_polygon(island[0], around))
+        })
+        .map(|(_, island)| island.clone())
+        .collect()
+}
+
+/// Face on the left of the edge from `from` to `to`, as the outline walked around it.
+fn walk_face(
+    vertices: &[Vec3],
+    neighbors: &[Vec<usize>],
+    mut from: usize,
+    mut to: usize,
+) -> Option<Vec<Vec3>> {
+    let (start_from, start_to) = (from, to);
+    let edges: usize = neighbors.iter().map(Vec::len).sum();
+    let mut outline = vec![vertices[from]];
+    for _ in 0..edges + 1 {
+        outline.push(vertices[to]);
+        let next = next_clockwise(vertices, &neighbors[to], from, to);
+        (from, to) = (to, next);
+        if (from, to) == (start_from, start_to) {
+            outline.pop();
+            return Some(outline);
+        }
+    }
+    None
+}
+
+/// Vertices merged within `MERGE_DISTANCE`, with every segment split where it meets another.
+fn planar_graph(segments: &[[Vec3; 2]]) -> (Vec<Vec3>, Vec<Vec<usize>>) {
+    let mut vertices: Vec<Vec3> = Vec::new();
+    let mut neighbors: Vec<Vec<usize>> = Vec::new();
+    let mut vertex = |position: Vec3, neighbors: &mut Vec<Vec<usize>>| {
+        vertices
+            .iter()
+            .position(|existing| existing.distance(position) < MERGE_DISTANCE)
+            .unwrap_or_else(|| {
+                vertices.push(position);
+                neighbors.push(Vec::new());
+                vertices.len() - 1
+            })
+    };
+
+    for (index, [start, end]) in segments.iter().enumerate() {
+        let mut cuts = vec![0., 1.];
+        for (other, [other_start, other_end]) in segments.iter().enumerate() {
+            if other != index
+                && let Some((t, _)) = segment_intersection(*start, *end, *other_start, *other_end)
+            {
+                cuts.push(t);
+            }
+        }
+        cuts.sort_by(f32::total_cmp);
+
+        for pair in cuts.windows(2) {
+            let a = vertex(start.lerp(*end, pair[0]), &mut neighbors);
+            let b = vertex(start.lerp(*end, pair[1]), &mut neighbors);
+            if a != b && !neighbors[a].contains(&b) {
+                neighbors[a].push(b);
+                neighbors[b].push(a);
+            }
+        }
+    }
+    (vertices, neighbors)
+}
+
+/// Neighbor of `at` reached by the first clockwise turn from the way back to `from`,
+/// which keeps the smallest region on the left.
+fn next_clockwise(vertices: &[Vec3], adjacent: &[usize], from: usize, at: usize) -> usize {
+    let back = xz_angle(vertices[from] - vertices[at]);
+    adjacent
+        .iter()
+        .copied()
+        .filter(|&next| next != from || adjacent.len() == 1)
+        .min_by(|&a, &b| {
+            let turn =
+                |next: usize| (back - xz_angle(vertices[next] - vertices[at])).rem_euclid(TAU);
+            turn(a).total_cmp(&turn(b))
+        })
+        .unwrap_or(adjacent[0])
+}
+
+/// Angle in the XZ plane, counter-clockwise from +X towards +Z.
+fn xz_angle(v: Vec3) -> f32 {
+    f32::atan2(v.z, v.x)
+}
+
+fn cross(a: Vec3, b: Vec3) -> f32 {
+    a.x * b.z - a.z * b.x
+}
+
+fn signed_area(points: &[Vec3]) -> f32 {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a.x * b.z - b.x * a.z)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(min: f32, max: f32) -> Vec<[Vec3; 2]> {
+        let corners = [
+            vec3(min, 0., min),
+            vec3(max, 0., min),
+            vec3(max, 0., max),
+            vec3(min, 0., max),
+        ];
+        (0..4)
+            .map(|index| [corners[index], corners[(index + 1) % 4]])
+            .collect()
+    }
+
+    #[test]
+    fn ray_passes_over_an_island() {
+        let mut segments = square(0., 3.);
+        segments.extend(square(1., 2.));
+        let outline = find_boundary(&segments, vec3(0.5, 0., 1.5)).unwrap();
+        assert_eq!(outline.len(), 4);
+        assert!((signed_area(&outline) - 18.).abs() < 1e-4);
+
+        let islands = find_islands(&segments, &outline);
+        assert_eq!(islands.len(), 1);
+        assert!((signed_area(&islands[0]) + 2.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn islands_inside_islands_are_left_out() {
+        let mut segments = square(0., 3.);
+        segments.extend(square(1., 2.));
+        segments.extend(square(1.25, 1.75));
+        let outline = find_boundary(&segments, vec3(0.5, 0., 1.5)).unwrap();
+        let islands = find_islands(&segments, &outline);
+        assert_eq!(islands.len(), 1);
+        assert!((signed_area(&islands[0]) + 2.).abs() < 1e-4);
+
+        // Inside the middle square, only the innermost one is an island
+        let outline = find_boundary(&segments, vec3(1.1, 0., 1.5)).unwrap();
+        assert!((signed_area(&outline) - 2.).abs() < 1e-4);
+        let islands = find_islands(&segments, &outline);
+        assert_eq!(islands.len(), 1);
+        assert!((signed_area(&islands[0]) + 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn touching_shapes_are_part_of_the_outline() {
+        let mut segments = square(0., 3.);
+        segments.push([vec3(3., 0., 1.5), vec3(2., 0., 1.5)]);
+        let outline = find_boundary(&segments, vec3(0.5, 0., 1.5)).unwrap();
+        assert!(find_islands(&segments, &outline).is_empty());
+    }
+}
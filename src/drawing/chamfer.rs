@@ -0,0 +1,302 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_simple_subsecond_system::*;
+
+use crate::cursor::Cursor;
+
+use super::{
+    draw::{CurrentDrawing, DEFAULT_POS, DrawMode, reset_current_drawing},
+    fillet::{CornerOwners, kept_side, trimmed, trimmed_endpoint},
+    geometry::line_intersection,
+    line::Line,
+    pick::pick_line,
+    size::{CHAMFER_DISTANCE, MERGE_DISTANCE},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChamferMode {
+    /// Setbacks along both lines
+    Distances,
+    /// Setback along the first line and the angle the cut leaves it at
+    DistanceAngle,
+}
+
+/// Cut the chamfer tool makes: `first` is the setback along the first picked line,
+/// then either `second` along the other line or `angle` from the first.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct ChamferSize {
+    pub mode: ChamferMode,
+    pub first: f32,
+    pub second: f32,
+    pub angle: f32,
+}
+
+impl Default for ChamferSize {
+    fn default() -> Self {
+        ChamferSize {
+            mode: ChamferMode::Distances,
+            first: CHAMFER_DISTANCE,
+            second: CHAMFER_DISTANCE,
+            angle: PI / 4.,
+        }
+    }
+}
+
+pub struct ChamferPlugin;
+
+impl Plugin for ChamferPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ChamferSize::default())
+            .add_systems(
+                Update,
+                (change_chamfer_size, handle_draw_chamfer).run_if(in_state(DrawMode::Chamfer)),
+            )
+            .add_systems(Update, display_chamfer_preview);
+    }
+}
+
+#[hot]
+fn change_chamfer_size(keyboard: Res<ButtonInput<KeyCode>>, mut size: ResMut<ChamferSize>) {
+    if keyboard.just_pressed(KeyCode::Tab) {
+        size.mode = match size.mode {
+            ChamferMode::Distances => ChamferMode::DistanceAngle,
+            ChamferMode::DistanceAngle => ChamferMode::Distances,
+        };
+    } else if keyboard.any_just_pressed([KeyCode::Equal, KeyCode::NumpadAdd]) {
+        size.first *= 1.25;
+    } else if keyboard.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        size.first /= 1.25;
+    } else if keyboard.just_pressed(KeyCode::BracketRight) {
+        match size.mode {
+            ChamferMode::Distances => size.second *= 1.25,
+            ChamferMode::DistanceAngle => size.angle = (size.angle + PI / 36.).min(PI * 35. / 36.),
+        }
+    } else if keyboard.just_pressed(KeyCode::BracketLeft) {
+        match size.mode {
+            ChamferMode::Distances => size.second /= 1.25,
+            ChamferMode::DistanceAngle => size.angle = (size.angle - PI / 36.).max(PI / 36.),
+        }
+    } else {
+        return;
+    }
+    info!("Chamfer: {:?}", *size);
+}
+
+#[hot]
+fn handle_draw_chamfer(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+    size: Res<ChamferSize>,
+    mut lines: Query<(Entity, &mut Line, Option<&ChildOf>)>,
+    mut owners: CornerOwners,
+) {
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Pick first line
+    if current_drawing.position[0] == DEFAULT_POS {
+        if pick_line(
+            lines.iter().map(|(entity, line, _)| (entity, line)),
+            cursor.position,
+        )
+        .is_some()
+        {
+            current_drawing.position[0] = cursor.position;
+        }
+        return;
+    }
+    let first_pick = current_drawing.position[0];
+    let second_pick = cursor.position;
+    let Some((first, _)) = pick_line(
+        lines.iter().map(|(entity, line, _)| (entity, line)),
+        first_pick,
+    ) else {
+        reset_current_drawing(current_drawing);
+        return;
+    };
+
+    // Pick second line
+    let Some((second, _)) = pick_line(
+        lines
+            .iter()
+            .filter(|(entity, _, _)| *entity != first)
+            .map(|(entity, line, _)| (entity, line)),
+        second_pick,
+    ) else {
+        return;
+    };
+    let Ok(
+        [
+            (_, mut first_line, first_owner),
+            (_, mut second_line, second_owner),
+        ],
+    ) = lines.get_many_mut([first, second])
+    else {
+        return;
+    };
+    let Some((trimmed_first, trimmed_second, cut)) =
+        chamfer(&first_line, first_pick, &second_line, second_pick, *size)
+    else {
+        warn!("Chamfer {:?} does not fit", *size);
+        return;
+    };
+
+    // Shapes of one outline that met at a corner stay one outline
+    let (first_corner, second_corner) = (
+        trimmed_endpoint(&first_line, &trimmed_first),
+        trimmed_endpoint(&second_line, &trimmed_second),
+    );
+    let owner = first_owner
+        .zip(second_owner)
+        .filter(|(first, second)| first.parent() == second.parent())
+        .filter(|_| first_corner.distance(second_corner) <= MERGE_DISTANCE)
+        .map(|(first, _)| first.parent());
+    if let Some(owner) = owner {
+        owners.replace_corner(owner, first_corner, vec![cut.start, cut.end]);
+    }
+    owners.move_dots(&[(first_corner, cut.start), (second_corner, cut.end)]);
+    owners.spawn_joint(cut, owner);
+    *first_line = trimmed_first;
+    *second_line = trimmed_second;
+    reset_current_drawing(current_drawing);
+}
+
+#[hot]
+fn display_chamfer_preview(
+    mut gizmos: Gizmos,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    current_drawing: Res<CurrentDrawing>,
+    size: Res<ChamferSize>,
+    lines: Query<(Entity, &Line)>,
+) {
+    let first_pick = current_drawing.position[0];
+    if state.get() != &DrawMode::Chamfer || first_pick == DEFAULT_POS {
+        return;
+    }
+    let Some((first, first_line)) = pick_line(lines.iter(), first_pick) else {
+        return;
+    };
+
+    // Display the cut the next click would make
+    if let Some((_, second_line)) = pick_line(
+        lines.iter().filter(|(entity, _)| *entity != first),
+        cursor.position,
+    ) && let Some((_, _, cut)) =
+        chamfer(first_line, first_pick, second_line, cursor.position, *size)
+    {
+        gizmos.line(cut.start, cut.end, Color::WHITE);
+    }
+}
+
+/// Both lines cut back from their intersection, and the line joining the cuts.
+fn chamfer(
+    first: &Line,
+    first_pick: Vec3,
+    second: &Line,
+    second_pick: Vec3,
+    size: ChamferSize,
+) -> Option<(Line, Line, Line)> {
+    let corner = line_intersection(first.start, first.end, second.start, second.end)?;
+    let (first_direction, first_far) = kept_side(first, first_pick, corner)?;
+    let (second_direction, second_far) = kept_side(second, second_pick, corner)?;
+
+    let second_setback = match size.mode {
+        ChamferMode::Distances => size.second,
+        ChamferMode::DistanceAngle => {
+            // Law of sines in the triangle between the corner and both cuts
+            let corner_angle = first_direction.angle_between(second_direction);
+            if size.angle + corner_angle >= PI {
+                return None;
+            }
+            size.first * size.angle.sin() / (size.angle + corner_angle).sin()
+        }
+    };
+    if size.first > (first_far - corner).dot(first_direction)
+        || second_setback > (second_far - corner).dot(second_direction)
+    {
+        return None;
+    }
+    let first_cut = corner + first_direction * size.first;
+    let second_cut = corner + second_direction * second_setback;
+
+    Some((
+        trimmed(first, first_far, first_cut),
+        trimmed(second, second_far, second_cut),
+        Line {
+            start: first_cut,
+            end: second_cut,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, FRAC_PI_6};
+
+    use super::*;
+
+    fn corner() -> (Line, Line) {
+        (
+            Line {
+                start: vec3(2., 0., 0.),
+                end: Vec3::ZERO,
+            },
+            Line {
+                start: Vec3::ZERO,
+                end: vec3(0., 0., 2.),
+            },
+        )
+    }
+
+    fn cut_at(angle: f32) -> Option<Line> {
+        let (first, second) = corner();
+        let size = ChamferSize {
+            mode: ChamferMode::DistanceAngle,
+            first: 0.5,
+            angle,
+            ..default()
+        };
+        chamfer(&first, first.start, &second, second.end, size).map(|(.., cut)| cut)
+    }
+
+    #[test]
+    fn distances_set_back_each_line() {
+        let (first, second) = corner();
+        let size = ChamferSize {
+            mode: ChamferMode::Distances,
+            first: 0.5,
+            second: 0.3,
+            ..default()
+        };
+        let (first, second, cut) = chamfer(&first, first.start, &second, second.end, size).unwrap();
+        assert!(first.end.distance(vec3(0.5, 0., 0.)) < 1e-5);
+        assert!(second.start.distance(vec3(0., 0., 0.3)) < 1e-5);
+        assert_eq!((cut.start, cut.end), (first.end, second.start));
+    }
+
+    #[test]
+    fn distance_angle_leaves_the_first_line_at_the_angle() {
+        let cut = cut_at(FRAC_PI_6).unwrap();
+        assert!(cut.start.distance(vec3(0.5, 0., 0.)) < 1e-5);
+        // Law of sines: 0.5 * sin(30°) / sin(180° - 90° - 30°)
+        assert!(cut.end.distance(vec3(0., 0., 0.5 * 0.5 / 0.75f32.sqrt())) < 1e-5);
+        let leaves_at = (cut.end - cut.start).angle_between(Vec3::ZERO - cut.start);
+        assert!((leaves_at - FRAC_PI_6).abs() < 1e-5);
+
+        let even = cut_at(FRAC_PI_4).unwrap();
+        assert!(even.end.distance(vec3(0., 0., 0.5)) < 1e-5);
+    }
+
+    #[test]
+    fn distance_angle_must_close_the_triangle() {
+        assert!(cut_at(FRAC_PI_2).is_none());
+    }
+}
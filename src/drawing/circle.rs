@@ -7,11 +7,12 @@ use crate::{
 };
 
 use super::{
+    construction::Construction,
     dot::Dot,
     draw::{CurrentDrawing, DEFAULT_POS, DEFAULT_RESOLUTION, DrawMode, reset_current_drawing},
 };
 
-#[derive(Component, Debug, Default)]
+#[derive(Component, Debug, Default, Clone, Copy)]
 pub struct Circle {
     pub center: Vec3,
     pub radius: f32,
@@ -21,7 +22,11 @@ pub struct CirclePlugin;
 
 impl Plugin for CirclePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, display_circles);
+        app.add_systems(
+            Update,
+            handle_draw_circle.run_if(in_state(DrawMode::Circle)),
+        )
+        .add_systems(Update, display_circles);
     }
 }
 
@@ -62,7 +67,7 @@ pub fn handle_draw_circle(
         ));
         commands.spawn((
             Circle {
-                center: center,
+                center,
                 radius: (end - center).length(),
             },
             Reloadable {
@@ -76,7 +81,7 @@ pub fn handle_draw_circle(
 #[hot]
 fn display_circles(
     mut gizmos: Gizmos,
-    query: Query<&Circle>,
+    query: Query<&Circle, Without<Construction>>,
     cursor: Res<Cursor>,
     state: Res<State<DrawMode>>,
     current_drawing: ResMut<CurrentDrawing>,
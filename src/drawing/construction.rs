@@ -0,0 +1,252 @@
+use std::f32::consts::TAU;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_simple_subsecond_system::*;
+
+use crate::{
+    cursor::Cursor,
+    reload::{ReloadLevel, Reloadable},
+};
+
+use super::{
+    arc::Arc,
+    bezier::Bezier,
+    circle::Circle,
+    draw::{CurrentDrawing, DEFAULT_POS, DEFAULT_RESOLUTION, DrawMode, reset_current_drawing},
+    ellipse::Ellipse,
+    line::Line,
+    pick::pick_curve,
+    select::SelectableShapes,
+    size::{DASH_LENGTH, XLINE_EXTENT},
+    spline::Spline,
+};
+
+pub const CONSTRUCTION_COLOR: Color = Color::srgba(1., 1., 1., 0.35);
+
+/// Reference geometry: drawn dashed and dimmed, and left out of meshes.
+#[derive(Component, Debug, Default)]
+pub struct Construction;
+
+/// Construction line through `point` running along `direction` without end.
+#[derive(Component, Debug)]
+#[require(Construction)]
+pub struct XLine {
+    pub point: Vec3,
+    pub direction: Vec3,
+}
+
+/// Shapes that can be flagged as construction geometry: the selectable ones and the curves.
+#[derive(SystemParam)]
+struct FlaggableShapes<'w, 's> {
+    shapes: SelectableShapes<'w, 's>,
+    ellipses: Query<'w, 's, (Entity, &'static Ellipse)>,
+    splines: Query<'w, 's, (Entity, &'static Spline)>,
+    beziers: Query<'w, 's, (Entity, &'static Bezier)>,
+}
+
+impl FlaggableShapes<'_, '_> {
+    /// `Dot`s only mark points, so this passes over them to the shape underneath.
+    fn pick(&self, point: Vec3) -> Option<Entity> {
+        self.shapes.pick_shape(point).or_else(|| {
+            let ellipses = self
+                .ellipses
+                .iter()
+                .map(|(entity, ellipse)| (entity, ellipse.positions()));
+            let splines = self
+                .splines
+                .iter()
+                .map(|(entity, spline)| (entity, spline.positions()));
+            let beziers = self
+                .beziers
+                .iter()
+                .map(|(entity, bezier)| (entity, bezier.positions()));
+            pick_curve(ellipses.chain(splines).chain(beziers), point)
+        })
+    }
+}
+
+pub struct ConstructionPlugin;
+
+impl Plugin for ConstructionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            toggle_construction.run_if(in_state(DrawMode::Construction)),
+        )
+        .add_systems(Update, handle_draw_xline.run_if(in_state(DrawMode::XLine)))
+        .add_systems(Update, (display_construction, display_xline_preview));
+    }
+}
+
+/// Clicking a shape flags it as construction geometry or back, along with an outline's pieces.
+#[hot]
+fn toggle_construction(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    shapes: FlaggableShapes,
+    construction: Query<(), With<Construction>>,
+    children: Query<&Children>,
+) {
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(entity) = shapes.pick(cursor.position) else {
+        return;
+    };
+
+    let flagged = construction.contains(entity);
+    let pieces = children
+        .get(entity)
+        .into_iter()
+        .flat_map(|children| children.iter());
+    for entity in std::iter::once(entity).chain(pieces) {
+        if flagged {
+            commands.entity(entity).remove::<Construction>();
+        } else {
+            commands.entity(entity).insert(Construction);
+        }
+    }
+}
+
+#[hot]
+fn handle_draw_xline(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+) {
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Define point the line passes through
+    if current_drawing.position[0] == DEFAULT_POS {
+        current_drawing.position[0] = cursor.position;
+        return;
+    }
+
+    // Define direction, keeping the point so several lines can fan out from it
+    let point = current_drawing.position[0];
+    let direction = (cursor.position - point).normalize_or_zero();
+    if direction == Vec3::ZERO {
+        return;
+    }
+    commands.spawn((
+        XLine { point, direction },
+        Reloadable {
+            level: ReloadLevel::Hard,
+        },
+    ));
+}
+
+/// Curves flagged as construction geometry, which their own display systems leave out.
+#[derive(SystemParam)]
+struct ConstructionCurves<'w, 's> {
+    ellipses: Query<'w, 's, &'static Ellipse, With<Construction>>,
+    splines: Query<'w, 's, &'static Spline, With<Construction>>,
+    beziers: Query<'w, 's, &'static Bezier, With<Construction>>,
+}
+
+#[hot]
+fn display_construction(
+    mut gizmos: Gizmos,
+    lines: Query<&Line, With<Construction>>,
+    arcs: Query<&Arc, With<Construction>>,
+    circles: Query<&Circle, With<Construction>>,
+    curves: ConstructionCurves,
+    xlines: Query<&XLine>,
+) {
+    for line in lines.iter() {
+        draw_dashed(&mut gizmos, &[line.start, line.end]);
+    }
+    for arc in arcs.iter() {
+        draw_dashed(&mut gizmos, &arc.positions());
+    }
+    for circle in circles.iter() {
+        let positions: Vec<Vec3> = (0..=DEFAULT_RESOLUTION)
+            .map(|index| {
+                circle.center
+                    + Quat::from_rotation_y(TAU * index as f32 / DEFAULT_RESOLUTION as f32)
+                        * Vec3::X
+                        * circle.radius
+            })
+            .collect();
+        draw_dashed(&mut gizmos, &positions);
+    }
+    for ellipse in curves.ellipses.iter() {
+        draw_dashed(&mut gizmos, &ellipse.positions());
+    }
+    for spline in curves.splines.iter() {
+        draw_dashed(&mut gizmos, &spline.positions());
+    }
+    for bezier in curves.beziers.iter() {
+        draw_dashed(&mut gizmos, &bezier.positions());
+    }
+    for xline in xlines.iter() {
+        draw_dashed(
+            &mut gizmos,
+            &[
+                xline.point - xline.direction * XLINE_EXTENT,
+                xline.point + xline.direction * XLINE_EXTENT,
+            ],
+        );
+    }
+}
+
+#[hot]
+fn display_xline_preview(
+    mut gizmos: Gizmos,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    current_drawing: Res<CurrentDrawing>,
+) {
+    // Display line through the point towards the cursor
+    let point = current_drawing.position[0];
+    if state.get() != &DrawMode::XLine || point == DEFAULT_POS {
+        return;
+    }
+    let direction = (cursor.position - point).normalize_or_zero();
+    draw_dashed(
+        &mut gizmos,
+        &[
+            point - direction * XLINE_EXTENT,
+            point + direction * XLINE_EXTENT,
+        ],
+    );
+}
+
+/// Draws the path through `positions` as dashes of `DASH_LENGTH` with gaps as long between them.
+fn draw_dashed(gizmos: &mut Gizmos, positions: &[Vec3]) {
+    let period = 2. * DASH_LENGTH;
+    let mut travelled = 0.;
+    for pair in positions.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        let length = start.distance(end);
+        let mut along = 0.;
+        while along < length {
+            let phase = (travelled + along) % period;
+            let dash = phase < DASH_LENGTH;
+            let step = if dash {
+                DASH_LENGTH - phase
+            } else {
+                period - phase
+            };
+            // Never step by less than a sliver, so rounding cannot stall the walk
+            let next = (along + step.max(DASH_LENGTH * 1e-3)).min(length);
+            if dash {
+                gizmos.line(
+                    start.lerp(end, along / length),
+                    start.lerp(end, next / length),
+                    CONSTRUCTION_COLOR,
+                );
+            }
+            along = next;
+        }
+        travelled += length;
+    }
+}
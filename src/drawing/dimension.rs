@@ -0,0 +1,699 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_simple_subsecond_system::*;
+
+use crate::{
+    cursor::Cursor,
+    reload::{ReloadLevel, Reloadable},
+};
+
+use super::{
+    arc::Arc,
+    circle::Circle,
+    dot::Dot,
+    draw::{CurrentDrawing, DEFAULT_POS, DrawMode, reset_current_drawing},
+    geometry::{line_intersection, plane_angle, signed_angle},
+    line::Line,
+    pick::{pick_arc, pick_circle, pick_dot, pick_line},
+    size::{ARROW_SIZE, TEXT_HEIGHT},
+    text::{Text, text_label},
+};
+
+/// Marks every kind of dimension, whose label is measured rather than typed.
+#[derive(Component, Debug, Default)]
+pub struct Dimension;
+
+/// Where a dimension measures from, kept as a reference so it follows edits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DimensionPoint {
+    Fixed(Vec3),
+    Dot(Entity),
+    LineStart(Entity),
+    LineEnd(Entity),
+}
+
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LinearOrientation {
+    #[default]
+    Aligned,
+    Horizontal,
+    Vertical,
+}
+
+/// Distance between two points, drawn `offset` away from `start` across the measured direction.
+#[derive(Component, Debug)]
+#[require(Dimension)]
+pub struct LinearDimension {
+    pub start: DimensionPoint,
+    pub end: DimensionPoint,
+    pub orientation: LinearOrientation,
+    pub offset: f32,
+}
+
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RadialKind {
+    #[default]
+    Radius,
+    Diameter,
+}
+
+/// Radius or diameter of a `Circle` or `Arc`.
+/// The leader leaves the center at `angle` and reaches `distance` out.
+#[derive(Component, Debug)]
+#[require(Dimension)]
+pub struct RadialDimension {
+    pub target: Entity,
+    pub kind: RadialKind,
+    pub angle: f32,
+    pub distance: f32,
+}
+
+/// Angle between two `Line`s, drawn as an arc `radius` away from where they cross.
+/// The flips pick which of the four corners between the lines is measured.
+#[derive(Component, Debug)]
+#[require(Dimension)]
+pub struct AngularDimension {
+    pub first: Entity,
+    pub second: Entity,
+    pub first_flip: bool,
+    pub second_flip: bool,
+    pub radius: f32,
+}
+
+/// Circles and arcs a radial dimension can measure.
+#[derive(SystemParam)]
+pub struct RoundShapes<'w, 's> {
+    circles: Query<'w, 's, (Entity, &'static Circle)>,
+    arcs: Query<'w, 's, (Entity, &'static Arc)>,
+}
+
+impl RoundShapes<'_, '_> {
+    /// `Circle` or `Arc` whose outline is under `point`.
+    fn pick(&self, point: Vec3) -> Option<Entity> {
+        pick_circle(self.circles.iter(), point)
+            .map(|(entity, _)| entity)
+            .or_else(|| pick_arc(self.arcs.iter(), point).map(|(entity, _)| entity))
+    }
+
+    /// Center and radius of a `Circle` or `Arc`.
+    fn round_of(&self, target: Entity) -> Option<(Vec3, f32)> {
+        if let Ok((_, circle)) = self.circles.get(target) {
+            return Some((circle.center, circle.radius));
+        }
+        self.arcs
+            .get(target)
+            .ok()
+            .map(|(_, arc)| (arc.center, arc.radius()))
+    }
+}
+
+/// Dots and lines a dimension can attach to.
+#[derive(SystemParam)]
+pub struct DimensionTargets<'w, 's> {
+    dots: Query<'w, 's, (Entity, &'static Dot)>,
+    lines: Query<'w, 's, (Entity, &'static Line)>,
+}
+
+pub struct DimensionPlugin;
+
+impl Plugin for DimensionPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LinearOrientation::default())
+            .insert_resource(RadialKind::default())
+            .add_systems(
+                Update,
+                (
+                    handle_draw_dimension.run_if(in_state(DrawMode::Dimension)),
+                    handle_draw_radial_dimension.run_if(in_state(DrawMode::RadialDimension)),
+                    handle_draw_angular_dimension.run_if(in_state(DrawMode::AngularDimension)),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    update_linear_dimensions,
+                    update_radial_dimensions,
+                    update_angular_dimensions,
+                    display_dimension_preview,
+                    display_radial_dimension_preview,
+                    display_angular_dimension_preview,
+                ),
+            );
+    }
+}
+
+#[hot]
+fn handle_draw_dimension(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+    mut orientation: ResMut<LinearOrientation>,
+    targets: DimensionTargets,
+) {
+    if keyboard.just_pressed(KeyCode::Tab) {
+        *orientation = match *orientation {
+            LinearOrientation::Aligned => LinearOrientation::Horizontal,
+            LinearOrientation::Horizontal => LinearOrientation::Vertical,
+            LinearOrientation::Vertical => LinearOrientation::Aligned,
+        };
+        info!("Dimension orientation: {:?}", *orientation);
+    }
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Define first point, or both ends at once when a line is picked
+    if current_drawing.position[0] == DEFAULT_POS {
+        if let Some((_, dot)) = pick_dot(targets.dots.iter(), cursor.position) {
+            current_drawing.position[0] = dot.position;
+        } else if let Some((_, line)) = pick_line(targets.lines.iter(), cursor.position) {
+            current_drawing.position[0] = line.start;
+            current_drawing.position[1] = line.end;
+        } else {
+            current_drawing.position[0] = cursor.position;
+        }
+    }
+    // Define second point
+    else if current_drawing.position[1] == DEFAULT_POS {
+        current_drawing.position[1] = pick_dot(targets.dots.iter(), cursor.position)
+            .map_or(cursor.position, |(_, dot)| dot.position);
+    }
+    // Define placement of the dimension line
+    else if current_drawing.position[2] == DEFAULT_POS {
+        current_drawing.position[2] = cursor.position;
+    }
+
+    let start = current_drawing.position[0];
+    let end = current_drawing.position[1];
+    let placement = current_drawing.position[2];
+
+    // Create dimension entity once both points and the placement are defined
+    if start != DEFAULT_POS && end != DEFAULT_POS && placement != DEFAULT_POS {
+        let (start_point, end_point) = match targets
+            .lines
+            .iter()
+            .find(|(_, line)| line.start == start && line.end == end)
+        {
+            Some((entity, _)) => (
+                DimensionPoint::LineStart(entity),
+                DimensionPoint::LineEnd(entity),
+            ),
+            None => (
+                resolve_dot(&targets.dots, start),
+                resolve_dot(&targets.dots, end),
+            ),
+        };
+        let direction = linear_direction(*orientation, start, end);
+
+        commands.spawn((
+            LinearDimension {
+                start: start_point,
+                end: end_point,
+                orientation: *orientation,
+                offset: (placement - start).dot(Vec3::Y.cross(direction)),
+            },
+            text_label(Text {
+                height: TEXT_HEIGHT,
+                ..default()
+            }),
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+        ));
+        reset_current_drawing(current_drawing);
+    }
+}
+
+/// Follows each dimension's references, redraws it and refreshes its measured label.
+#[hot]
+fn update_linear_dimensions(
+    mut commands: Commands,
+    mut gizmos: Gizmos,
+    mut query: Query<(Entity, &LinearDimension, &mut Text)>,
+    dots: Query<&Dot>,
+    lines: Query<&Line>,
+) {
+    for (entity, dimension, mut text) in query.iter_mut() {
+        // Dimensions go away with the geometry they measure
+        let (Some(start), Some(end)) = (
+            position_of(dimension.start, &dots, &lines),
+            position_of(dimension.end, &dots, &lines),
+        ) else {
+            commands.entity(entity).try_despawn();
+            continue;
+        };
+
+        let label = draw_linear_dimension(
+            &mut gizmos,
+            start,
+            end,
+            dimension.orientation,
+            dimension.offset,
+        );
+        set_label(&mut text, label);
+    }
+}
+
+#[hot]
+fn display_dimension_preview(
+    mut gizmos: Gizmos,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    orientation: Res<LinearOrientation>,
+    current_drawing: ResMut<CurrentDrawing>,
+) {
+    // Display currently placed dimension
+    let start = current_drawing.position[0];
+    if state.get() != &DrawMode::Dimension || start == DEFAULT_POS {
+        return;
+    }
+
+    let end = current_drawing.position[1];
+    if end == DEFAULT_POS {
+        gizmos.line(start, cursor.position, Color::WHITE);
+        return;
+    }
+    let direction = linear_direction(*orientation, start, end);
+    draw_linear_dimension(
+        &mut gizmos,
+        start,
+        end,
+        *orientation,
+        (cursor.position - start).dot(Vec3::Y.cross(direction)),
+    );
+}
+
+#[hot]
+fn handle_draw_radial_dimension(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+    mut kind: ResMut<RadialKind>,
+    shapes: RoundShapes,
+) {
+    if keyboard.just_pressed(KeyCode::Tab) {
+        *kind = match *kind {
+            RadialKind::Radius => RadialKind::Diameter,
+            RadialKind::Diameter => RadialKind::Radius,
+        };
+        info!("Radial dimension: {:?}", *kind);
+    }
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Define measured circle or arc
+    if current_drawing.position[0] == DEFAULT_POS {
+        if shapes.pick(cursor.position).is_some() {
+            current_drawing.position[0] = cursor.position;
+        }
+        return;
+    }
+    // Define end of leader and create dimension entity
+    let Some(target) = shapes.pick(current_drawing.position[0]) else {
+        reset_current_drawing(current_drawing);
+        return;
+    };
+    let Some((center, _)) = shapes.round_of(target) else {
+        return;
+    };
+
+    commands.spawn((
+        RadialDimension {
+            target,
+            kind: *kind,
+            angle: plane_angle(cursor.position - center),
+            distance: (cursor.position - center).length(),
+        },
+        text_label(Text {
+            height: TEXT_HEIGHT,
+            ..default()
+        }),
+        Reloadable {
+            level: ReloadLevel::Hard,
+        },
+    ));
+    reset_current_drawing(current_drawing);
+}
+
+#[hot]
+fn handle_draw_angular_dimension(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+    lines: Query<(Entity, &Line)>,
+) {
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    let picked = pick_line(lines.iter(), cursor.position).map(|(entity, _)| entity);
+    let first = pick_line(lines.iter(), current_drawing.position[0]).map(|(entity, _)| entity);
+
+    // Define first line
+    if current_drawing.position[0] == DEFAULT_POS {
+        if picked.is_some() {
+            current_drawing.position[0] = cursor.position;
+        }
+        return;
+    }
+    // Define second line, which has to be another one
+    if current_drawing.position[1] == DEFAULT_POS {
+        if picked.is_some() && picked != first {
+            current_drawing.position[1] = cursor.position;
+        }
+        return;
+    }
+
+    // Define placement of the dimension arc and create dimension entity
+    let (Some((first, first_line)), Some((second, second_line))) = (
+        pick_line(lines.iter(), current_drawing.position[0]),
+        pick_line(lines.iter(), current_drawing.position[1]),
+    ) else {
+        reset_current_drawing(current_drawing);
+        return;
+    };
+    // Parallel lines have no angle between them
+    let Some((first_flip, second_flip, radius)) =
+        angular_placement(first_line, second_line, cursor.position)
+    else {
+        return;
+    };
+
+    commands.spawn((
+        AngularDimension {
+            first,
+            second,
+            first_flip,
+            second_flip,
+            radius,
+        },
+        text_label(Text {
+            height: TEXT_HEIGHT,
+            ..default()
+        }),
+        Reloadable {
+            level: ReloadLevel::Hard,
+        },
+    ));
+    reset_current_drawing(current_drawing);
+}
+
+#[hot]
+fn update_radial_dimensions(
+    mut commands: Commands,
+    mut gizmos: Gizmos,
+    mut query: Query<(Entity, &RadialDimension, &mut Text)>,
+    shapes: RoundShapes,
+) {
+    for (entity, dimension, mut text) in query.iter_mut() {
+        let Some((center, radius)) = shapes.round_of(dimension.target) else {
+            commands.entity(entity).try_despawn();
+            continue;
+        };
+        let label = draw_radial_dimension(
+            &mut gizmos,
+            center,
+            radius,
+            dimension.kind,
+            dimension.angle,
+            dimension.distance,
+        );
+        set_label(&mut text, label);
+    }
+}
+
+#[hot]
+fn update_angular_dimensions(
+    mut commands: Commands,
+    mut gizmos: Gizmos,
+    mut query: Query<(Entity, &AngularDimension, &mut Text)>,
+    lines: Query<&Line>,
+) {
+    for (entity, dimension, mut text) in query.iter_mut() {
+        let (Ok(first), Ok(second)) = (lines.get(dimension.first), lines.get(dimension.second))
+        else {
+            commands.entity(entity).try_despawn();
+            continue;
+        };
+        if let Some(label) = draw_angular_dimension(
+            &mut gizmos,
+            first,
+            second,
+            dimension.first_flip,
+            dimension.second_flip,
+            dimension.radius,
+        ) {
+            set_label(&mut text, label);
+        }
+    }
+}
+
+#[hot]
+fn display_radial_dimension_preview(
+    mut gizmos: Gizmos,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    kind: Res<RadialKind>,
+    current_drawing: ResMut<CurrentDrawing>,
+    shapes: RoundShapes,
+) {
+    // Display currently placed radial dimension
+    if state.get() != &DrawMode::RadialDimension || current_drawing.position[0] == DEFAULT_POS {
+        return;
+    }
+    let Some((center, radius)) = shapes
+        .pick(current_drawing.position[0])
+        .and_then(|target| shapes.round_of(target))
+    else {
+        return;
+    };
+    draw_radial_dimension(
+        &mut gizmos,
+        center,
+        radius,
+        *kind,
+        plane_angle(cursor.position - center),
+        (cursor.position - center).length(),
+    );
+}
+
+#[hot]
+fn display_angular_dimension_preview(
+    mut gizmos: Gizmos,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    current_drawing: ResMut<CurrentDrawing>,
+    lines: Query<(Entity, &Line)>,
+) {
+    // Display currently placed angular dimension
+    if state.get() != &DrawMode::AngularDimension || current_drawing.position[1] == DEFAULT_POS {
+        return;
+    }
+    let (Some((_, first)), Some((_, second))) = (
+        pick_line(lines.iter(), current_drawing.position[0]),
+        pick_line(lines.iter(), current_drawing.position[1]),
+    ) else {
+        return;
+    };
+    if let Some((first_flip, second_flip, radius)) =
+        angular_placement(first, second, cursor.position)
+    {
+        draw_angular_dimension(&mut gizmos, first, second, first_flip, second_flip, radius);
+    }
+}
+
+fn linear_direction(orientation: LinearOrientation, start: Vec3, end: Vec3) -> Vec3 {
+    match orientation {
+        LinearOrientation::Aligned => (end - start).normalize_or(Vec3::X),
+        LinearOrientation::Horizontal => Vec3::X,
+        LinearOrientation::Vertical => Vec3::Z,
+    }
+}
+
+/// Draws extension lines, dimension line and arrows, and returns the label to show.
+fn draw_linear_dimension(
+    gizmos: &mut Gizmos,
+    start: Vec3,
+    end: Vec3,
+    orientation: LinearOrientation,
+    offset: f32,
+) -> Text {
+    let direction = linear_direction(orientation, start, end);
+    let normal = Vec3::Y.cross(direction);
+    let base = start + normal * offset;
+    let start_foot = start + normal * (base - start).dot(normal);
+    let end_foot = end + normal * (base - end).dot(normal);
+    let side = normal * offset.signum();
+
+    gizmos.line(start, start_foot + side * ARROW_SIZE, Color::WHITE);
+    gizmos.line(end, end_foot + side * ARROW_SIZE, Color::WHITE);
+    gizmos.line(start_foot, end_foot, Color::WHITE);
+    draw_arrow(gizmos, start_foot, start_foot - end_foot);
+    draw_arrow(gizmos, end_foot, end_foot - start_foot);
+
+    Text {
+        position: (start_foot + end_foot) / 2. + side * TEXT_HEIGHT,
+        height: TEXT_HEIGHT,
+        rotation: plane_angle(direction),
+        content: format!("{:.2}", (end - start).dot(direction).abs()),
+    }
+}
+
+/// Draws the leader and arrows of a radius or diameter callout, and returns the label to show.
+fn draw_radial_dimension(
+    gizmos: &mut Gizmos,
+    center: Vec3,
+    radius: f32,
+    kind: RadialKind,
+    angle: f32,
+    distance: f32,
+) -> Text {
+    let direction = Quat::from_rotation_y(angle) * Vec3::X;
+    let rim = center + direction * radius;
+    let leader_end = center + direction * distance.max(radius);
+
+    let content = match kind {
+        RadialKind::Radius => {
+            gizmos.line(center, leader_end, Color::WHITE);
+            format!("R{:.2}", radius)
+        }
+        RadialKind::Diameter => {
+            let opposite = center - direction * radius;
+            gizmos.line(opposite, leader_end, Color::WHITE);
+            draw_arrow(gizmos, opposite, -direction);
+            format!("Ø{:.2}", radius * 2.)
+        }
+    };
+    draw_arrow(gizmos, rim, direction);
+
+    Text {
+        position: leader_end + direction * TEXT_HEIGHT,
+        height: TEXT_HEIGHT,
+        rotation: angle,
+        content,
+    }
+}
+
+/// Draws the dimension arc, extension lines and arrows between two lines,
+/// and returns the label to show. Parallel lines have nothing to draw.
+fn draw_angular_dimension(
+    gizmos: &mut Gizmos,
+    first: &Line,
+    second: &Line,
+    first_flip: bool,
+    second_flip: bool,
+    radius: f32,
+) -> Option<Text> {
+    let vertex = line_intersection(first.start, first.end, second.start, second.end)?;
+    let first_ray = line_ray(first, first_flip);
+    let second_ray = line_ray(second, second_flip);
+    let arc = Arc::new(
+        vertex,
+        vertex + first_ray * radius,
+        signed_angle(first_ray, second_ray),
+    );
+
+    // Extend lines that stop short of the dimension arc
+    for (line, ray) in [(first, first_ray), (second, second_ray)] {
+        let reach = (line.start - vertex)
+            .dot(ray)
+            .max((line.end - vertex).dot(ray));
+        if reach < radius {
+            gizmos.line(
+                vertex + ray * reach.max(0.),
+                vertex + ray * (radius + ARROW_SIZE),
+                Color::WHITE,
+            );
+        }
+    }
+    gizmos.linestrip(arc.positions(), Color::WHITE);
+    draw_arrow(
+        gizmos,
+        arc.start,
+        -Vec3::Y.cross(first_ray) * arc.sweep.signum(),
+    );
+    draw_arrow(gizmos, arc.end, arc.end_direction());
+
+    let bisector = Quat::from_rotation_y(arc.sweep / 2.) * first_ray;
+    Some(Text {
+        position: vertex + bisector * (radius + TEXT_HEIGHT),
+        height: TEXT_HEIGHT,
+        rotation: plane_angle(bisector) - FRAC_PI_2,
+        content: format!("{:.1}°", arc.sweep.abs().to_degrees()),
+    })
+}
+
+/// Arrowhead with its tip at `tip`, pointing along `direction`.
+pub fn draw_arrow(gizmos: &mut Gizmos, tip: Vec3, direction: Vec3) {
+    let back = -direction.normalize_or_zero() * ARROW_SIZE;
+    let spread = Vec3::Y.cross(back) * 0.3;
+    gizmos.line(tip, tip + back + spread, Color::WHITE);
+    gizmos.line(tip, tip + back - spread, Color::WHITE);
+}
+
+fn resolve_dot(dots: &Query<(Entity, &Dot)>, position: Vec3) -> DimensionPoint {
+    dots.iter()
+        .find(|(_, dot)| dot.position == position)
+        .map_or(DimensionPoint::Fixed(position), |(entity, _)| {
+            DimensionPoint::Dot(entity)
+        })
+}
+
+fn position_of(point: DimensionPoint, dots: &Query<&Dot>, lines: &Query<&Line>) -> Option<Vec3> {
+    match point {
+        DimensionPoint::Fixed(position) => Some(position),
+        DimensionPoint::Dot(entity) => dots.get(entity).ok().map(|dot| dot.position),
+        DimensionPoint::LineStart(entity) => lines.get(entity).ok().map(|line| line.start),
+        DimensionPoint::LineEnd(entity) => lines.get(entity).ok().map(|line| line.end),
+    }
+}
+
+fn line_ray(line: &Line, flip: bool) -> Vec3 {
+    let direction = (line.end - line.start).normalize_or_zero();
+    if flip { -direction } else { direction }
+}
+
+/// Which directions of each line bound the corner holding `placement`, and how far out it is.
+fn angular_placement(first: &Line, second: &Line, placement: Vec3) -> Option<(bool, bool, f32)> {
+    let vertex = line_intersection(first.start, first.end, second.start, second.end)?;
+    let first_ray = line_ray(first, false);
+    let second_ray = line_ray(second, false);
+    let offset = placement - vertex;
+
+    // Solve offset = a * first_ray + b * second_ray on the plane
+    let determinant = first_ray.x * second_ray.z - first_ray.z * second_ray.x;
+    let a = (offset.x * second_ray.z - offset.z * second_ray.x) / determinant;
+    let b = (first_ray.x * offset.z - first_ray.z * offset.x) / determinant;
+    Some((a < 0., b < 0., offset.length()))
+}
+
+/// Copies a freshly measured label over, leaving it untouched when nothing moved.
+fn set_label(text: &mut Mut<Text>, label: Text) {
+    if text.position != label.position
+        || text.rotation != label.rotation
+        || text.content != label.content
+    {
+        text.position = label.position;
+        text.rotation = label.rotation;
+        text.content = label.content;
+    }
+}
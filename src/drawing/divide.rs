@@ -0,0 +1,228 @@
+use std::f32::consts::TAU;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_simple_subsecond_system::*;
+
+use crate::{
+    cursor::Cursor,
+    reload::{ReloadLevel, Reloadable},
+};
+
+use super::{
+    arc::Arc,
+    circle::Circle,
+    dot::Dot,
+    draw::DrawMode,
+    line::Line,
+    offset::Segment,
+    pick::{pick_arc, pick_circle, pick_line},
+    size::{DOT_RADIUS, MEASURE_DISTANCE, MERGE_DISTANCE},
+};
+
+pub const MAX_DIVISIONS: u32 = 100;
+
+/// Number of equal parts to divide into, and the distance between measured points.
+#[derive(Resource, Debug, PartialEq)]
+pub struct DivideOptions {
+    pub segments: u32,
+    pub distance: f32,
+}
+
+impl Default for DivideOptions {
+    fn default() -> Self {
+        DivideOptions {
+            segments: 4,
+            distance: MEASURE_DISTANCE,
+        }
+    }
+}
+
+/// Lines, arcs and circles to place points along.
+#[derive(SystemParam)]
+pub struct DivideShapes<'w, 's> {
+    lines: Query<'w, 's, (Entity, &'static Line)>,
+    arcs: Query<'w, 's, (Entity, &'static Arc)>,
+    circles: Query<'w, 's, (Entity, &'static Circle)>,
+}
+
+impl DivideShapes<'_, '_> {
+    /// Path under `point`, and whether it closes on itself.
+    /// Circles run a full turn from their +X point.
+    fn pick(&self, point: Vec3) -> Option<(Segment, bool)> {
+        if let Some((_, line)) = pick_line(self.lines.iter(), point) {
+            Some((Segment::Line(*line), false))
+        } else if let Some((_, arc)) = pick_arc(self.arcs.iter(), point) {
+            Some((Segment::Arc(*arc), false))
+        } else {
+            pick_circle(self.circles.iter(), point).map(|(_, circle)| {
+                (
+                    Segment::Arc(Arc::new(
+                        circle.center,
+                        circle.center + Vec3::X * circle.radius,
+                        TAU,
+                    )),
+                    true,
+                )
+            })
+        }
+    }
+}
+
+pub struct DividePlugin;
+
+impl Plugin for DividePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DivideOptions::default())
+            .add_systems(
+                Update,
+                (
+                    change_divide_options,
+                    handle_draw_divide.run_if(in_state(DrawMode::Divide)),
+                    handle_draw_measure.run_if(in_state(DrawMode::Measure)),
+                )
+                    .chain(),
+            )
+            .add_systems(Update, display_divide_preview);
+    }
+}
+
+#[hot]
+fn change_divide_options(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    state: Res<State<DrawMode>>,
+    mut options: ResMut<DivideOptions>,
+) {
+    let more = keyboard.any_just_pressed([KeyCode::Equal, KeyCode::NumpadAdd]);
+    let fewer = keyboard.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]);
+    if !(more || fewer) {
+        return;
+    }
+
+    match state.get() {
+        DrawMode::Divide if more => options.segments = (options.segments + 1).min(MAX_DIVISIONS),
+        DrawMode::Divide => options.segments = (options.segments - 1).max(2),
+        DrawMode::Measure if more => options.distance *= 1.25,
+        DrawMode::Measure => options.distance /= 1.25,
+        _ => return,
+    }
+    info!("Divide: {:?}", *options);
+}
+
+#[hot]
+fn handle_draw_divide(
+    commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    options: Res<DivideOptions>,
+    shapes: DivideShapes,
+    dots: Query<&Dot>,
+) {
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some((path, closed)) = shapes.pick(cursor.position) else {
+        return;
+    };
+    spawn_dots(
+        commands,
+        &dots,
+        divide_points(&path, closed, options.segments),
+    );
+}
+
+#[hot]
+fn handle_draw_measure(
+    commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    options: Res<DivideOptions>,
+    shapes: DivideShapes,
+    dots: Query<&Dot>,
+) {
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some((path, closed)) = shapes.pick(cursor.position) else {
+        return;
+    };
+    spawn_dots(
+        commands,
+        &dots,
+        measure_points(&path, closed, options.distance, cursor.position),
+    );
+}
+
+#[hot]
+fn display_divide_preview(
+    mut gizmos: Gizmos,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    options: Res<DivideOptions>,
+    shapes: DivideShapes,
+) {
+    // Mark where the points would go on the shape under the cursor
+    let points = match state.get() {
+        DrawMode::Divide | DrawMode::Measure => {
+            let Some((path, closed)) = shapes.pick(cursor.position) else {
+                return;
+            };
+            if state.get() == &DrawMode::Divide {
+                divide_points(&path, closed, options.segments)
+            } else {
+                measure_points(&path, closed, options.distance, cursor.position)
+            }
+        }
+        _ => return,
+    };
+    for point in points {
+        gizmos.circle(
+            Isometry3d::new(point, Quat::from_rotation_arc(Vec3::Z, Vec3::Y)),
+            DOT_RADIUS,
+            Color::WHITE,
+        );
+    }
+}
+
+/// Spawns a `Dot` on each point that does not already have one.
+fn spawn_dots(mut commands: Commands, dots: &Query<&Dot>, points: Vec<Vec3>) {
+    for point in points {
+        if dots
+            .iter()
+            .any(|dot| dot.position.distance(point) <= MERGE_DISTANCE)
+        {
+            continue;
+        }
+        commands.spawn((
+            Dot { position: point },
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+        ));
+    }
+}
+
+/// Points splitting `path` into `segments` parts of equal length.
+/// Open paths leave out their ends, closed ones keep their start.
+fn divide_points(path: &Segment, closed: bool, segments: u32) -> Vec<Vec3> {
+    let first = if closed { 0 } else { 1 };
+    (first..segments)
+        .map(|index| path.point_at(index as f32 / segments as f32))
+        .collect()
+}
+
+/// Points every `distance` along `path`, counted from the end nearer to `click`.
+/// Closed paths get one on their start as well.
+fn measure_points(path: &Segment, closed: bool, distance: f32, click: Vec3) -> Vec<Vec3> {
+    let path = if !closed && path.end().distance(click) < path.start().distance(click) {
+        path.reversed()
+    } else {
+        *path
+    };
+    let length = path.length();
+    let first = if closed { 0 } else { 1 };
+    (first..=MAX_DIVISIONS)
+        .map(|index| index as f32 * distance)
+        .take_while(|along| *along < length - MERGE_DISTANCE)
+        .map(|along| path.point_at(along / length))
+        .collect()
+}
@@ -6,7 +6,7 @@ use crate::{
     reload::{ReloadLevel, Reloadable},
 };
 
-use super::size::DOT_RADIUS;
+use super::{draw::DrawMode, size::DOT_RADIUS};
 
 #[derive(Component, Debug, Default)]
 pub struct Dot {
@@ -17,7 +17,8 @@ pub struct DotPlugin;
 
 impl Plugin for DotPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, display_dots);
+        app.add_systems(Update, handle_draw_dot.run_if(in_state(DrawMode::Dot)))
+            .add_systems(Update, display_dots);
     }
 }
 
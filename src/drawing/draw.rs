@@ -1,13 +1,30 @@
-use crate::cursor::Cursor;
 use crate::drawing::dot::*;
 use crate::drawing::line::*;
 
 use bevy::prelude::*;
 use bevy_simple_subsecond_system::*;
 
-use super::arc::{ArcPlugin, handle_draw_arc};
-use super::circle::{CirclePlugin, handle_draw_circle};
-use super::rectangle::{RectanglePlugin, handle_draw_rectangle};
+use super::arc::ArcPlugin;
+use super::array::ArrayPlugin;
+use super::bezier::BezierPlugin;
+use super::chamfer::ChamferPlugin;
+use super::circle::CirclePlugin;
+use super::construction::ConstructionPlugin;
+use super::dimension::DimensionPlugin;
+use super::divide::DividePlugin;
+use super::ellipse::EllipsePlugin;
+use super::fillet::FilletPlugin;
+use super::freehand::FreehandPlugin;
+use super::hatch::HatchPlugin;
+use super::mirror::MirrorPlugin;
+use super::offset::OffsetPlugin;
+use super::polygon::PolygonPlugin;
+use super::rectangle::RectanglePlugin;
+use super::select::SelectPlugin;
+use super::slot::SlotPlugin;
+use super::spline::SplinePlugin;
+use super::text::{TextEditing, TextPlugin, edit_text};
+use super::trim::TrimPlugin;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, States, Default, Reflect)]
 pub enum DrawMode {
@@ -16,8 +33,35 @@ pub enum DrawMode {
     Dot,
     Line,
     Rectangle,
+    CenterRectangle,
+    RotatedRectangle,
     Circle,
     Arc,
+    ThreePointArc,
+    Ellipse,
+    Polygon,
+    Bezier,
+    Spline,
+    Freehand,
+    Text,
+    Dimension,
+    RadialDimension,
+    AngularDimension,
+    Hatch,
+    Fillet,
+    Chamfer,
+    Offset,
+    Trim,
+    Extend,
+    Mirror,
+    RectangularArray,
+    PolarArray,
+    PathArray,
+    Construction,
+    XLine,
+    Divide,
+    Measure,
+    Slot,
 }
 
 pub const DEFAULT_RESOLUTION: u32 = 64;
@@ -36,9 +80,14 @@ impl Default for CurrentDrawing {
     }
 }
 
-#[derive(Resource, Default, Debug, PartialEq, PartialOrd)]
+#[derive(Resource, Default, Debug, PartialEq)]
 pub struct LineChain {
     pub count: u32,
+    pub points: Vec<Vec3>,
+    pub polyline: Option<Entity>,
+    /// Direction the chain is heading at its last point
+    pub direction: Vec3,
+    pub tangent_arc: bool,
 }
 
 pub struct DrawPlugin;
@@ -53,7 +102,26 @@ impl Plugin for DrawPlugin {
             .add_plugins(RectanglePlugin)
             .add_plugins(CirclePlugin)
             .add_plugins(ArcPlugin)
-            .add_systems(Update, (change_draw_mode, handle_drawing).chain());
+            .add_plugins(EllipsePlugin)
+            .add_plugins(PolygonPlugin)
+            .add_plugins(BezierPlugin)
+            .add_plugins(SplinePlugin)
+            .add_plugins(FreehandPlugin)
+            .add_plugins(TextPlugin)
+            .add_plugins(DimensionPlugin)
+            .add_plugins(HatchPlugin)
+            .add_plugins(FilletPlugin)
+            .add_plugins(ChamferPlugin)
+            .add_plugins(OffsetPlugin)
+            .add_plugins(TrimPlugin)
+            .add_plugins(SelectPlugin)
+            .add_plugins(MirrorPlugin)
+            .add_plugins(ArrayPlugin)
+            .add_plugins(ConstructionPlugin)
+            .add_plugins(DividePlugin)
+            .add_plugins(SlotPlugin)
+            // Read mode keys before edit_text can end an edit on the same press
+            .add_systems(Update, change_draw_mode.before(edit_text));
     }
 }
 
@@ -63,7 +131,12 @@ fn change_draw_mode(
     mut state: ResMut<NextState<DrawMode>>,
     current_drawing: ResMut<CurrentDrawing>,
     line_chain: ResMut<LineChain>,
+    text_editing: Res<TextEditing>,
 ) {
+    // Keys type into the edited annotation instead of switching modes
+    if text_editing.entity.is_some() {
+        return;
+    }
     if keyboard.just_pressed(KeyCode::Escape) {
         reset_drawing(current_drawing, line_chain);
         state.set(DrawMode::None);
@@ -75,49 +148,105 @@ fn change_draw_mode(
         state.set(DrawMode::Line);
     } else if keyboard.just_pressed(KeyCode::KeyR) {
         reset_drawing(current_drawing, line_chain);
-        state.set(DrawMode::Rectangle);
+        if keyboard.pressed(KeyCode::ShiftLeft) {
+            state.set(DrawMode::CenterRectangle);
+        } else if keyboard.pressed(KeyCode::AltLeft) {
+            state.set(DrawMode::RotatedRectangle);
+        } else {
+            state.set(DrawMode::Rectangle);
+        }
     } else if keyboard.just_pressed(KeyCode::KeyC) {
         reset_drawing(current_drawing, line_chain);
         state.set(DrawMode::Circle);
     } else if keyboard.just_pressed(KeyCode::KeyA) {
         reset_drawing(current_drawing, line_chain);
-        state.set(DrawMode::Arc);
-    }
-}
-
-#[hot]
-fn handle_drawing(
-    commands: Commands,
-    mouse_input: Res<ButtonInput<MouseButton>>,
-    state: Res<State<DrawMode>>,
-    cursor: Res<Cursor>,
-    current_drawing: ResMut<CurrentDrawing>,
-    line_chain: ResMut<LineChain>,
-) {
-    match state.get() {
-        DrawMode::Dot => {
-            handle_draw_dot(commands, mouse_input, cursor);
+        if keyboard.pressed(KeyCode::ShiftLeft) {
+            state.set(DrawMode::ThreePointArc);
+        } else {
+            state.set(DrawMode::Arc);
         }
-        DrawMode::Line => {
-            handle_draw_line(commands, mouse_input, cursor, current_drawing, line_chain);
+    } else if keyboard.just_pressed(KeyCode::KeyE) {
+        reset_drawing(current_drawing, line_chain);
+        state.set(DrawMode::Ellipse);
+    } else if keyboard.just_pressed(KeyCode::KeyP) {
+        reset_drawing(current_drawing, line_chain);
+        state.set(DrawMode::Polygon);
+    } else if keyboard.just_pressed(KeyCode::KeyB) {
+        reset_drawing(current_drawing, line_chain);
+        state.set(DrawMode::Bezier);
+    } else if keyboard.just_pressed(KeyCode::KeyN) {
+        reset_drawing(current_drawing, line_chain);
+        state.set(DrawMode::Spline);
+    } else if keyboard.just_pressed(KeyCode::KeyF) {
+        reset_drawing(current_drawing, line_chain);
+        state.set(DrawMode::Freehand);
+    } else if keyboard.just_pressed(KeyCode::KeyT) {
+        reset_drawing(current_drawing, line_chain);
+        state.set(DrawMode::Text);
+    } else if keyboard.just_pressed(KeyCode::KeyM) {
+        reset_drawing(current_drawing, line_chain);
+        if keyboard.pressed(KeyCode::ShiftLeft) {
+            state.set(DrawMode::RadialDimension);
+        } else if keyboard.pressed(KeyCode::AltLeft) {
+            state.set(DrawMode::AngularDimension);
+        } else {
+            state.set(DrawMode::Dimension);
         }
-        DrawMode::Rectangle => {
-            handle_draw_rectangle(commands, mouse_input, cursor, current_drawing);
+    } else if keyboard.just_pressed(KeyCode::KeyH) {
+        reset_drawing(current_drawing, line_chain);
+        state.set(DrawMode::Hatch);
+    } else if keyboard.just_pressed(KeyCode::KeyG) {
+        reset_drawing(current_drawing, line_chain);
+        if keyboard.pressed(KeyCode::ShiftLeft) {
+            state.set(DrawMode::Chamfer);
+        } else {
+            state.set(DrawMode::Fillet);
         }
-        DrawMode::Circle => {
-            handle_draw_circle(commands, mouse_input, cursor, current_drawing);
+    } else if keyboard.just_pressed(KeyCode::KeyO) {
+        reset_drawing(current_drawing, line_chain);
+        state.set(DrawMode::Offset);
+    } else if keyboard.just_pressed(KeyCode::KeyX) {
+        reset_drawing(current_drawing, line_chain);
+        if keyboard.pressed(KeyCode::ShiftLeft) {
+            state.set(DrawMode::Extend);
+        } else {
+            state.set(DrawMode::Trim);
         }
-        DrawMode::Arc => handle_draw_arc(commands, mouse_input, cursor, current_drawing),
-        _ => {
-            return;
+    } else if keyboard.just_pressed(KeyCode::KeyI) {
+        reset_drawing(current_drawing, line_chain);
+        state.set(DrawMode::Mirror);
+    } else if keyboard.just_pressed(KeyCode::KeyU) {
+        reset_drawing(current_drawing, line_chain);
+        if keyboard.pressed(KeyCode::ShiftLeft) {
+            state.set(DrawMode::PolarArray);
+        } else if keyboard.pressed(KeyCode::AltLeft) {
+            state.set(DrawMode::PathArray);
+        } else {
+            state.set(DrawMode::RectangularArray);
+        }
+    } else if keyboard.just_pressed(KeyCode::KeyQ) {
+        reset_drawing(current_drawing, line_chain);
+        state.set(DrawMode::Construction);
+    } else if keyboard.just_pressed(KeyCode::KeyL) {
+        reset_drawing(current_drawing, line_chain);
+        state.set(DrawMode::XLine);
+    } else if keyboard.just_pressed(KeyCode::KeyV) {
+        reset_drawing(current_drawing, line_chain);
+        if keyboard.pressed(KeyCode::ShiftLeft) {
+            state.set(DrawMode::Measure);
+        } else {
+            state.set(DrawMode::Divide);
         }
+    } else if keyboard.just_pressed(KeyCode::KeyW) {
+        reset_drawing(current_drawing, line_chain);
+        state.set(DrawMode::Slot);
     }
 }
 
 #[hot]
 pub fn reset_drawing(current_drawing: ResMut<CurrentDrawing>, mut line_chain: ResMut<LineChain>) {
     reset_current_drawing(current_drawing);
-    line_chain.count = 0
+    *line_chain = LineChain::default();
 }
 
 #[hot]
@@ -0,0 +1,161 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy_simple_subsecond_system::*;
+
+use crate::{
+    cursor::Cursor,
+    reload::{ReloadLevel, Reloadable},
+};
+
+use super::{
+    construction::Construction,
+    dot::Dot,
+    draw::{CurrentDrawing, DEFAULT_POS, DEFAULT_RESOLUTION, DrawMode, reset_current_drawing},
+};
+
+#[derive(Component, Debug, Default)]
+pub struct Ellipse {
+    pub center: Vec3,
+    pub major_axis: Vec3,
+    pub minor_radius: f32,
+}
+
+impl Ellipse {
+    /// Points around the ellipse, as many as on a `DEFAULT_RESOLUTION` circle.
+    pub fn positions(&self) -> Vec<Vec3> {
+        let minor_axis = Vec3::Y.cross(self.major_axis).normalize_or_zero() * self.minor_radius;
+        (0..=DEFAULT_RESOLUTION)
+            .map(|step| {
+                let angle = TAU * step as f32 / DEFAULT_RESOLUTION as f32;
+                self.center + self.major_axis * angle.cos() + minor_axis * angle.sin()
+            })
+            .collect()
+    }
+}
+
+pub struct EllipsePlugin;
+
+impl Plugin for EllipsePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            handle_draw_ellipse.run_if(in_state(DrawMode::Ellipse)),
+        )
+        .add_systems(Update, display_ellipses);
+    }
+}
+
+#[hot]
+pub fn handle_draw_ellipse(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+) {
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Define center of ellipse
+    if current_drawing.position[0] == DEFAULT_POS {
+        current_drawing.position[0] = cursor.position;
+    }
+    // Define end of major axis
+    else if current_drawing.position[1] == DEFAULT_POS {
+        current_drawing.position[1] = cursor.position;
+    }
+    // Define point on minor axis
+    else if current_drawing.position[2] == DEFAULT_POS {
+        current_drawing.position[2] = cursor.position;
+    }
+
+    let center = current_drawing.position[0];
+    let major = current_drawing.position[1];
+    let minor = current_drawing.position[2];
+
+    // Create ellipse entity if center, major and minor are all defined
+    if center != DEFAULT_POS && major != DEFAULT_POS && minor != DEFAULT_POS {
+        commands.spawn((
+            Dot { position: center },
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+        ));
+        commands.spawn((
+            Ellipse {
+                center,
+                major_axis: major - center,
+                minor_radius: minor_radius(center, major - center, minor),
+            },
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+        ));
+        reset_current_drawing(current_drawing);
+    }
+}
+
+#[hot]
+fn display_ellipses(
+    mut gizmos: Gizmos,
+    query: Query<&Ellipse, Without<Construction>>,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    current_drawing: ResMut<CurrentDrawing>,
+) {
+    // Display existing ellipses
+    for ellipse in query.iter() {
+        gizmos
+            .ellipse(
+                Isometry3d::new(ellipse.center, ellipse_rotation(ellipse.major_axis)),
+                vec2(ellipse.major_axis.length(), ellipse.minor_radius),
+                Color::WHITE,
+            )
+            .resolution(DEFAULT_RESOLUTION);
+    }
+    // Display currently drawn ellipse
+    if state.get() != &DrawMode::Ellipse || current_drawing.position[0] == DEFAULT_POS {
+        return;
+    }
+
+    let center = current_drawing.position[0];
+    let major = current_drawing.position[1];
+
+    let (major_axis, minor_radius) = if major != DEFAULT_POS {
+        (
+            major - center,
+            minor_radius(center, major - center, cursor.position),
+        )
+    } else {
+        (
+            cursor.position - center,
+            (cursor.position - center).length(),
+        )
+    };
+    gizmos
+        .ellipse(
+            Isometry3d::new(center, ellipse_rotation(major_axis)),
+            vec2(major_axis.length(), minor_radius),
+            Color::WHITE,
+        )
+        .resolution(DEFAULT_RESOLUTION);
+    gizmos.line(center, center + major_axis, Color::WHITE);
+}
+
+/// Rotation that lays the gizmo's local X axis along `major_axis` on the Y=0 plane.
+fn ellipse_rotation(major_axis: Vec3) -> Quat {
+    Quat::from_rotation_y(f32::atan2(-major_axis.z, major_axis.x))
+        * Quat::from_rotation_arc(Vec3::Z, Dir3::Y.as_vec3())
+}
+
+/// Distance from `point` to the major axis line through `center`.
+fn minor_radius(center: Vec3, major_axis: Vec3, point: Vec3) -> f32 {
+    let direction = major_axis.normalize_or_zero();
+    let offset = point - center;
+    (offset - direction * offset.dot(direction)).length()
+}
@@ -0,0 +1,585 @@
+use std::f32::consts::TAU;
+
+use bevy::{ecs::system::SystemParam, input::mouse::AccumulatedMouseScroll, prelude::*};
+use bevy_simple_subsecond_system::*;
+
+use crate::{
+    cursor::Cursor,
+    reload::{ReloadLevel, Reloadable},
+};
+
+use super::{
+    arc::Arc,
+    dot::Dot,
+    draw::{CurrentDrawing, DEFAULT_POS, DrawMode, reset_current_drawing},
+    geometry::{line_intersection, plane_angle, signed_angle},
+    line::Line,
+    pick::{pick_arc, pick_line},
+    polygon::{Polygon, polygon_vertices},
+    polyline::Polyline,
+    rectangle::Rectangle,
+    size::{FILLET_RADIUS, MERGE_DISTANCE},
+    slot::Slot,
+};
+
+/// Radius of the arcs the fillet tool inserts.
+#[derive(Resource, Debug, PartialEq)]
+pub struct FilletRadius {
+    pub radius: f32,
+}
+
+impl Default for FilletRadius {
+    fn default() -> Self {
+        FilletRadius {
+            radius: FILLET_RADIUS,
+        }
+    }
+}
+
+/// What a corner edit touches besides the trimmed shapes: the `Dot`s on their endpoints,
+/// and the polyline, rectangle or polygon owning them.
+#[derive(SystemParam)]
+pub struct CornerOwners<'w, 's> {
+    commands: Commands<'w, 's>,
+    dots: Query<'w, 's, &'static mut Dot>,
+    polylines: Query<'w, 's, &'static mut Polyline>,
+    rectangles: Query<'w, 's, &'static Rectangle>,
+    polygons: Query<'w, 's, &'static Polygon>,
+    slots: Query<'w, 's, (), With<Slot>>,
+}
+
+impl CornerOwners<'_, '_> {
+    /// Moves the `Dot` on each trimmed endpoint along with it.
+    /// A corner shared by both shapes gets a second `Dot` once the first has moved away.
+    pub fn move_dots(&mut self, moves: &[(Vec3, Vec3)]) {
+        let marked: Vec<bool> = moves
+            .iter()
+            .map(|(from, _)| {
+                self.dots
+                    .iter()
+                    .any(|dot| dot.position.distance(*from) <= MERGE_DISTANCE)
+            })
+            .collect();
+        for (&(from, to), marked) in moves.iter().zip(marked) {
+            if !marked {
+                continue;
+            }
+            match self
+                .dots
+                .iter_mut()
+                .find(|dot| dot.position.distance(from) <= MERGE_DISTANCE)
+            {
+                Some(mut dot) => dot.position = to,
+                None => {
+                    self.commands.spawn((
+                        Dot { position: to },
+                        Reloadable {
+                            level: ReloadLevel::Hard,
+                        },
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Replaces `corner` of the outline owned by `owner` with `points`.
+    /// A rectangle or polygon stops being one once a corner is cut, so it becomes a closed polyline.
+    /// A slot just stops being one, keeping its pieces grouped.
+    pub fn replace_corner(&mut self, owner: Entity, corner: Vec3, points: Vec<Vec3>) {
+        if let Ok(mut polyline) = self.polylines.get_mut(owner) {
+            polyline.replace_corner(corner, points);
+        } else if let Ok(rectangle) = self.rectangles.get(owner) {
+            let mut polyline = Polyline {
+                points: rectangle.corners().to_vec(),
+                closed: true,
+            };
+            if polyline.replace_corner(corner, points) {
+                self.commands
+                    .entity(owner)
+                    .remove::<Rectangle>()
+                    .insert(polyline);
+            }
+        } else if let Ok(polygon) = self.polygons.get(owner) {
+            let mut polyline = Polyline {
+                points: polygon_vertices(polygon.center, polygon.vertex, polygon.sides),
+                closed: true,
+            };
+            if polyline.replace_corner(corner, points) {
+                self.commands
+                    .entity(owner)
+                    .remove::<Polygon>()
+                    .insert(polyline);
+            }
+        } else if self.slots.contains(owner) {
+            self.commands.entity(owner).remove::<Slot>();
+        }
+    }
+
+    /// Spawns a shape joining a cut corner, owned by the same outline as the shapes it joins.
+    pub fn spawn_joint(&mut self, joint: impl Bundle, owner: Option<Entity>) {
+        let mut entity = self.commands.spawn((
+            joint,
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+        ));
+        if let Some(owner) = owner {
+            entity.insert(ChildOf(owner));
+        }
+    }
+}
+
+pub struct FilletPlugin;
+
+impl Plugin for FilletPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(FilletRadius::default())
+            .add_systems(
+                Update,
+                (change_fillet_radius, handle_draw_fillet).run_if(in_state(DrawMode::Fillet)),
+            )
+            .add_systems(Update, display_fillet_preview);
+    }
+}
+
+#[hot]
+fn change_fillet_radius(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse_scroll: Res<AccumulatedMouseScroll>,
+    mut fillet_radius: ResMut<FilletRadius>,
+) {
+    if keyboard.any_just_pressed([KeyCode::Equal, KeyCode::NumpadAdd]) || mouse_scroll.delta.y > 0.
+    {
+        fillet_radius.radius *= 1.25;
+    } else if keyboard.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract])
+        || mouse_scroll.delta.y < 0.
+    {
+        fillet_radius.radius /= 1.25;
+    } else {
+        return;
+    }
+    info!("Fillet radius: {:?}", fillet_radius.radius);
+}
+
+#[hot]
+fn handle_draw_fillet(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+    fillet_radius: Res<FilletRadius>,
+    mut lines: Query<(Entity, &mut Line, Option<&ChildOf>)>,
+    mut arcs: Query<(Entity, &mut Arc, Option<&ChildOf>)>,
+    mut owners: CornerOwners,
+) {
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Pick first line
+    if current_drawing.position[0] == DEFAULT_POS {
+        if pick_line(
+            lines.iter().map(|(entity, line, _)| (entity, line)),
+            cursor.position,
+        )
+        .is_some()
+        {
+            current_drawing.position[0] = cursor.position;
+        }
+        return;
+    }
+    let first_pick = current_drawing.position[0];
+    let second_pick = cursor.position;
+    let Some((first, _)) = pick_line(
+        lines.iter().map(|(entity, line, _)| (entity, line)),
+        first_pick,
+    ) else {
+        reset_current_drawing(current_drawing);
+        return;
+    };
+
+    // Pick second line, or an arc
+    let second_line = pick_line(
+        lines
+            .iter()
+            .filter(|(entity, _, _)| *entity != first)
+            .map(|(entity, line, _)| (entity, line)),
+        second_pick,
+    )
+    .map(|(entity, _)| entity);
+    let second_arc = pick_arc(
+        arcs.iter().map(|(entity, arc, _)| (entity, arc)),
+        second_pick,
+    )
+    .map(|(entity, _)| entity);
+
+    if let Some(second) = second_line {
+        let Ok(
+            [
+                (_, mut first_line, first_owner),
+                (_, mut second_line, second_owner),
+            ],
+        ) = lines.get_many_mut([first, second])
+        else {
+            return;
+        };
+        let Some((trimmed_first, trimmed_second, arc)) = line_fillet(
+            &first_line,
+            first_pick,
+            &second_line,
+            second_pick,
+            fillet_radius.radius,
+        ) else {
+            warn!("Radius {:?} does not fit", fillet_radius.radius);
+            return;
+        };
+
+        // Shapes of one outline that met at a corner stay one outline
+        let (first_corner, second_corner) = (
+            trimmed_endpoint(&first_line, &trimmed_first),
+            trimmed_endpoint(&second_line, &trimmed_second),
+        );
+        let owner = first_owner
+            .zip(second_owner)
+            .filter(|(first, second)| first.parent() == second.parent())
+            .filter(|_| first_corner.distance(second_corner) <= MERGE_DISTANCE)
+            .map(|(first, _)| first.parent());
+        if let Some(owner) = owner {
+            owners.replace_corner(owner, first_corner, arc.positions());
+        }
+        owners.move_dots(&[(first_corner, arc.start), (second_corner, arc.end)]);
+        owners.spawn_joint(arc, owner);
+        *first_line = trimmed_first;
+        *second_line = trimmed_second;
+    } else if let Some(second) = second_arc {
+        let (Ok((_, mut line, line_owner)), Ok((_, mut second_arc, arc_owner))) =
+            (lines.get_mut(first), arcs.get_mut(second))
+        else {
+            return;
+        };
+        let Some((trimmed_line, trimmed_arc, arc)) = line_arc_fillet(
+            &line,
+            first_pick,
+            &second_arc,
+            second_pick,
+            fillet_radius.radius,
+        ) else {
+            warn!("Radius {:?} does not fit", fillet_radius.radius);
+            return;
+        };
+
+        // Shapes of one outline that met at a corner stay one outline
+        let corner = trimmed_endpoint(&line, &trimmed_line);
+        let arc_corner = if trimmed_arc.start.distance(second_arc.start) > MERGE_DISTANCE {
+            second_arc.start
+        } else {
+            second_arc.end
+        };
+        let owner = line_owner
+            .zip(arc_owner)
+            .filter(|(line_owner, arc_owner)| line_owner.parent() == arc_owner.parent())
+            .filter(|_| corner.distance(arc_corner) <= MERGE_DISTANCE)
+            .map(|(line_owner, _)| line_owner.parent());
+        if let Some(owner) = owner {
+            owners.replace_corner(owner, corner, arc.positions());
+        }
+        // Arcs have no Dots on their ends, so only the line's endpoint moves
+        owners.move_dots(&[(corner, arc.start)]);
+        owners.spawn_joint(arc, line_owner.map(|owner| owner.parent()));
+        *line = trimmed_line;
+        *second_arc = trimmed_arc;
+    } else {
+        return;
+    }
+    reset_current_drawing(current_drawing);
+}
+
+#[hot]
+fn display_fillet_preview(
+    mut gizmos: Gizmos,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    current_drawing: Res<CurrentDrawing>,
+    fillet_radius: Res<FilletRadius>,
+    lines: Query<(Entity, &Line)>,
+    arcs: Query<(Entity, &Arc)>,
+) {
+    let first_pick = current_drawing.position[0];
+    if state.get() != &DrawMode::Fillet || first_pick == DEFAULT_POS {
+        return;
+    }
+    let Some((first, first_line)) = pick_line(lines.iter(), first_pick) else {
+        return;
+    };
+
+    // Display the arc the next click would insert
+    let arc = if let Some((_, second_line)) = pick_line(
+        lines.iter().filter(|(entity, _)| *entity != first),
+        cursor.position,
+    ) {
+        line_fillet(
+            first_line,
+            first_pick,
+            second_line,
+            cursor.position,
+            fillet_radius.radius,
+        )
+        .map(|(_, _, arc)| arc)
+    } else if let Some((_, second_arc)) = pick_arc(arcs.iter(), cursor.position) {
+        line_arc_fillet(
+            first_line,
+            first_pick,
+            second_arc,
+            cursor.position,
+            fillet_radius.radius,
+        )
+        .map(|(_, _, arc)| arc)
+    } else {
+        None
+    };
+    if let Some(arc) = arc {
+        gizmos.linestrip(arc.positions(), Color::WHITE);
+    }
+}
+
+/// Direction from `corner` along the part of `line` around `pick`, and the endpoint that part keeps.
+pub fn kept_side(line: &Line, pick: Vec3, corner: Vec3) -> Option<(Vec3, Vec3)> {
+    let direction = (line.end - line.start).normalize_or_zero();
+    let direction = if (pick - corner).dot(direction) < 0. {
+        -direction
+    } else {
+        direction
+    };
+    let far = if (line.start - corner).dot(direction) > (line.end - corner).dot(direction) {
+        line.start
+    } else {
+        line.end
+    };
+    (direction != Vec3::ZERO && (far - corner).dot(direction) > 0.).then_some((direction, far))
+}
+
+/// `line` cut back or extended to run from `far` to `end`, keeping its original orientation.
+pub fn trimmed(line: &Line, far: Vec3, end: Vec3) -> Line {
+    if line.start == far {
+        Line { start: far, end }
+    } else {
+        Line {
+            start: end,
+            end: far,
+        }
+    }
+}
+
+/// Endpoint of `line` that moved when it became `trimmed`.
+pub fn trimmed_endpoint(line: &Line, trimmed: &Line) -> Vec3 {
+    if line.start == trimmed.start {
+        line.end
+    } else {
+        line.start
+    }
+}
+
+/// Both lines trimmed to an arc of `radius` tangent to each, keeping the parts around the picks.
+fn line_fillet(
+    first: &Line,
+    first_pick: Vec3,
+    second: &Line,
+    second_pick: Vec3,
+    radius: f32,
+) -> Option<(Line, Line, Arc)> {
+    let corner = line_intersection(first.start, first.end, second.start, second.end)?;
+    let (first_direction, first_far) = kept_side(first, first_pick, corner)?;
+    let (second_direction, second_far) = kept_side(second, second_pick, corner)?;
+
+    let half = first_direction.angle_between(second_direction) / 2.;
+    let setback = radius / half.tan();
+    if !setback.is_finite()
+        || setback > (first_far - corner).dot(first_direction)
+        || setback > (second_far - corner).dot(second_direction)
+    {
+        return None;
+    }
+    let first_tangent = corner + first_direction * setback;
+    let second_tangent = corner + second_direction * setback;
+    let center = corner + (first_direction + second_direction).normalize() * radius / half.sin();
+
+    Some((
+        trimmed(first, first_far, first_tangent),
+        trimmed(second, second_far, second_tangent),
+        Arc::new(
+            center,
+            first_tangent,
+            signed_angle(first_tangent - center, second_tangent - center),
+        ),
+    ))
+}
+
+/// `line` and `arc` trimmed to an arc of `radius` tangent to both.
+/// Of the possible fillets, takes the one touching down closest to the picks.
+fn line_arc_fillet(
+    line: &Line,
+    line_pick: Vec3,
+    arc: &Arc,
+    arc_pick: Vec3,
+    radius: f32,
+) -> Option<(Line, Arc, Arc)> {
+    let direction = (line.end - line.start).normalize_or_zero();
+    let normal = Vec3::Y.cross(direction);
+    let arc_radius = arc.radius();
+
+    // Centers sit on the line moved out by `radius` and on a circle around the arc's center
+    let mut best: Option<(f32, Vec3, Vec3, Vec3)> = None;
+    for side in [-1., 1.] {
+        for distance in [arc_radius + radius, arc_radius - radius] {
+            let origin = line.start + normal * side * radius;
+            let closest = origin + direction * (arc.center - origin).dot(direction);
+            let gap = distance * distance - (arc.center - closest).length_squared();
+            if distance <= 0. || gap < 0. {
+                continue;
+            }
+            for sign in [-1., 1.] {
+                let center = closest + direction * sign * gap.sqrt();
+                let line_tangent = center - normal * side * radius;
+                let arc_tangent = arc.center + (center - arc.center).normalize() * arc_radius;
+                let score = line_tangent.distance(line_pick) + arc_tangent.distance(arc_pick);
+                if best.is_none_or(|(best_score, ..)| score < best_score) {
+                    best = Some((score, center, line_tangent, arc_tangent));
+                }
+            }
+        }
+    }
+    let (_, center, line_tangent, arc_tangent) = best?;
+    let (_, far) = kept_side(line, line_pick, line_tangent)?;
+
+    Some((
+        trimmed(line, far, line_tangent),
+        trimmed_arc(arc, arc_tangent, arc_pick),
+        Arc::new(
+            center,
+            line_tangent,
+            signed_angle(line_tangent - center, arc_tangent - center),
+        ),
+    ))
+}
+
+/// `arc` cut back to `point` on the side of `pick`, or extended to it from its nearer end.
+pub fn trimmed_arc(arc: &Arc, point: Vec3, pick: Vec3) -> Arc {
+    let sign = arc.sweep.signum();
+    let sweep = arc.sweep.abs();
+    let along = |position: Vec3| {
+        ((plane_angle(position - arc.center) - plane_angle(arc.start - arc.center)) * sign)
+            .rem_euclid(TAU)
+    };
+    let point_along = along(point);
+
+    if point_along > sweep {
+        if point_along - sweep < TAU - point_along {
+            Arc::new(arc.center, arc.start, sign * point_along)
+        } else {
+            Arc::new(arc.center, point, sign * (sweep + TAU - point_along))
+        }
+    } else if along(pick) < point_along {
+        Arc::new(arc.center, arc.start, sign * point_along)
+    } else {
+        Arc::new(arc.center, point, sign * (sweep - point_along))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    use super::*;
+
+    fn close(a: Vec3, b: Vec3) -> bool {
+        a.distance(b) < 1e-4
+    }
+
+    #[test]
+    fn line_fillet_rounds_a_square_corner() {
+        let first = Line {
+            start: vec3(2., 0., 0.),
+            end: Vec3::ZERO,
+        };
+        let second = Line {
+            start: Vec3::ZERO,
+            end: vec3(0., 0., 2.),
+        };
+        let (first, second, arc) =
+            line_fillet(&first, vec3(1., 0., 0.), &second, vec3(0., 0., 1.), 0.5).unwrap();
+
+        // Both lines keep their orientation and stop on the tangent points
+        assert!(close(first.start, vec3(2., 0., 0.)) && close(first.end, vec3(0.5, 0., 0.)));
+        assert!(close(second.start, vec3(0., 0., 0.5)) && close(second.end, vec3(0., 0., 2.)));
+        assert!(close(arc.center, vec3(0.5, 0., 0.5)));
+        assert!(close(arc.start, first.end) && close(arc.end, second.start));
+        assert!((arc.sweep.abs() - FRAC_PI_2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn line_fillet_needs_room() {
+        let first = Line {
+            start: vec3(1., 0., 0.),
+            end: Vec3::ZERO,
+        };
+        let second = Line {
+            start: Vec3::ZERO,
+            end: vec3(0., 0., 1.),
+        };
+        assert!(line_fillet(&first, first.start, &second, second.end, 2.).is_none());
+    }
+
+    #[test]
+    fn line_arc_fillet_is_tangent_to_both() {
+        let line = Line {
+            start: vec3(-2., 0., 0.),
+            end: vec3(2., 0., 0.),
+        };
+        let arc = Arc::new(vec3(0., 0., -1.), vec3(1.5, 0., -1.), -FRAC_PI_2);
+        let radius = 0.2;
+        // The corner between the left of the line and the arc below it, near x = 1.1
+        let arc_pick = arc.center + Quat::from_rotation_y(-0.3) * Vec3::X * 1.5;
+        let (line, arc_kept, fillet) =
+            line_arc_fillet(&line, vec3(0.5, 0., 0.), &arc, arc_pick, radius).unwrap();
+
+        assert!((fillet.radius() - radius).abs() < 1e-4);
+        assert!((fillet.center.z.abs() - radius).abs() < 1e-4);
+        let to_arc = fillet.center.distance(arc.center);
+        assert!(
+            (to_arc - (arc.radius() + radius)).abs() < 1e-4
+                || (to_arc - (arc.radius() - radius)).abs() < 1e-4
+        );
+        assert!(close(line.start, vec3(-2., 0., 0.)) && close(line.end, fillet.start));
+        assert!((fillet.end.distance(arc.center) - arc.radius()).abs() < 1e-4);
+        assert!(close(arc_kept.start, arc.start) && close(arc_kept.end, fillet.end));
+    }
+
+    #[test]
+    fn trimmed_arc_keeps_the_picked_side() {
+        let arc = Arc::new(Vec3::ZERO, Vec3::X, PI);
+        let point = Vec3::NEG_Z;
+
+        let start_side = trimmed_arc(&arc, point, Quat::from_rotation_y(FRAC_PI_4) * Vec3::X);
+        assert!(close(start_side.start, Vec3::X) && close(start_side.end, point));
+
+        let end_side = trimmed_arc(&arc, point, Quat::from_rotation_y(3. * FRAC_PI_4) * Vec3::X);
+        assert!(close(end_side.start, point) && close(end_side.end, Vec3::NEG_X));
+    }
+
+    #[test]
+    fn trimmed_arc_extends_from_the_nearer_end() {
+        let arc = Arc::new(Vec3::ZERO, Vec3::X, PI);
+
+        let past_end = Quat::from_rotation_y(5. * FRAC_PI_4) * Vec3::X;
+        let extended = trimmed_arc(&arc, past_end, Vec3::NEG_X);
+        assert!(close(extended.start, Vec3::X) && close(extended.end, past_end));
+
+        let before_start = Quat::from_rotation_y(-FRAC_PI_4) * Vec3::X;
+        let extended = trimmed_arc(&arc, before_start, Vec3::X);
+        assert!(close(extended.start, before_start) && close(extended.end, Vec3::NEG_X));
+        assert!((extended.sweep - 5. * FRAC_PI_4).abs() < 1e-4);
+    }
+}
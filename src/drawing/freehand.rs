@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use bevy_simple_subsecond_system::*;
+
+use crate::cursor::Cursor;
+
+use super::{
+    draw::{CurrentDrawing, DrawMode, LineChain, reset_drawing},
+    geometry::simplify,
+    polyline::spawn_polyline,
+    size::{CLOSE_RADIUS, FREEHAND_TOLERANCE},
+};
+
+pub struct FreehandPlugin;
+
+impl Plugin for FreehandPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            handle_draw_freehand.run_if(in_state(DrawMode::Freehand)),
+        )
+        .add_systems(Update, display_freehand);
+    }
+}
+
+#[hot]
+pub fn handle_draw_freehand(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    current_drawing: ResMut<CurrentDrawing>,
+    mut line_chain: ResMut<LineChain>,
+) {
+    // Sample the stroke while the button is held
+    if mouse_input.pressed(MouseButton::Left) {
+        if line_chain.points.last() != Some(&cursor.position) {
+            line_chain.points.push(cursor.position);
+        }
+        return;
+    }
+    if !mouse_input.just_released(MouseButton::Left) {
+        return;
+    }
+
+    // Commit the simplified stroke, closing it when it ends where it began
+    let mut points = simplify(&line_chain.points, FREEHAND_TOLERANCE);
+    let closed =
+        points.len() > 3 && (points[points.len() - 1] - points[0]).length() <= CLOSE_RADIUS;
+    if closed {
+        points.pop();
+    }
+    if points.len() >= 2 {
+        spawn_polyline(&mut commands, points, closed);
+    }
+    reset_drawing(current_drawing, line_chain);
+}
+
+#[hot]
+fn display_freehand(mut gizmos: Gizmos, state: Res<State<DrawMode>>, line_chain: Res<LineChain>) {
+    // Display currently drawn stroke
+    if state.get() == &DrawMode::Freehand {
+        gizmos.linestrip(line_chain.points.iter().copied(), Color::WHITE);
+    }
+}
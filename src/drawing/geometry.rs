@@ -0,0 +1,204 @@
+use std::f32::consts::{PI, TAU};
+
+use bevy::prelude::*;
+
+/// Center of the circle through three points on the Y=0 plane, if they are not collinear.
+pub fn circle_through(a: Vec3, b: Vec3, c: Vec3) -> Option<Vec3> {
+    let d = 2. * (a.x * (b.z - c.z) + b.x * (c.z - a.z) + c.x * (a.z - b.z));
+    if d.abs() < f32::EPSILON {
+        return None;
+    }
+    let (a2, b2, c2) = (a.length_squared(), b.length_squared(), c.length_squared());
+    Some(vec3(
+        (a2 * (b.z - c.z) + b2 * (c.z - a.z) + c2 * (a.z - b.z)) / d,
+        0.,
+        (a2 * (c.x - b.x) + b2 * (a.x - c.x) + c2 * (b.x - a.x)) / d,
+    ))
+}
+
+/// Angle of `v` around the +Y axis, measured from +X the way `Quat::from_rotation_y` turns.
+pub fn plane_angle(v: Vec3) -> f32 {
+    f32::atan2(-v.z, v.x)
+}
+
+/// Shortest signed angle around +Y that turns `from` onto `to`, in (-PI, PI].
+pub fn signed_angle(from: Vec3, to: Vec3) -> f32 {
+    let angle = (plane_angle(to) - plane_angle(from)).rem_euclid(TAU);
+    if angle > PI { angle - TAU } else { angle }
+}
+
+/// Distance from `point` to the segment between `start` and `end`.
+pub fn distance_to_segment(point: Vec3, start: Vec3, end: Vec3) -> f32 {
+    let segment = end - start;
+    let t = if segment.length_squared() > 0. {
+        ((point - start).dot(segment) / segment.length_squared()).clamp(0., 1.)
+    } else {
+        0.
+    };
+    (point - (start + segment * t)).length()
+}
+
+/// Ramer-Douglas-Peucker: drops points that stay within `tolerance` of the simplified path.
+pub fn simplify(points: &[Vec3], tolerance: f32) -> Vec<Vec3> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let (start, end) = (points[0], points[points.len() - 1]);
+    let (index, distance) = points[1..points.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(index, point)| (index + 1, distance_to_segment(*point, start, end)))
+        .fold((0, 0.), |farthest, candidate| {
+            if candidate.1 > farthest.1 {
+                candidate
+            } else {
+                farthest
+            }
+        });
+
+    if distance <= tolerance {
+        return vec![start, end];
+    }
+    let mut simplified = simplify(&points[..=index], tolerance);
+    simplified.pop();
+    simplified.extend(simplify(&points[index..], tolerance));
+    simplified
+}
+
+/// Where the infinite lines through `a_start`-`a_end` and `b_start`-`b_end` cross.
+pub fn line_intersection(a_start: Vec3, a_end: Vec3, b_start: Vec3, b_end: Vec3) -> Option<Vec3> {
+    let a = a_end - a_start;
+    let b = b_end - b_start;
+    let denominator = a.x * b.z - a.z * b.x;
+    if denominator.abs() < f32::EPSILON {
+        return None;
+    }
+    let offset = b_start - a_start;
+    let t = (offset.x * b.z - offset.z * b.x) / denominator;
+    Some(a_start + a * t)
+}
+
+/// Mirror image of `point` across the line through `axis_start` and `axis_end`.
+pub fn reflect(point: Vec3, axis_start: Vec3, axis_end: Vec3) -> Vec3 {
+    let direction = (axis_end - axis_start).normalize_or_zero();
+    let foot = axis_start + direction * (point - axis_start).dot(direction);
+    2. * foot - point
+}
+
+/// Where the infinite line through `start` and `end` crosses the circle around `center`.
+pub fn line_circle_intersections(start: Vec3, end: Vec3, center: Vec3, radius: f32) -> Vec<Vec3> {
+    let direction = (end - start).normalize_or_zero();
+    let closest = start + direction * (center - start).dot(direction);
+    let gap = radius * radius - (center - closest).length_squared();
+    if direction == Vec3::ZERO || gap < 0. {
+        return Vec::new();
+    }
+    let gap = gap.sqrt();
+    vec![closest - direction * gap, closest + direction * gap]
+}
+
+/// Where the circles around `a_center` and `b_center` cross.
+pub fn circle_intersections(
+    a_center: Vec3,
+    a_radius: f32,
+    b_center: Vec3,
+    b_radius: f32,
+) -> Vec<Vec3> {
+    let offset = b_center - a_center;
+    let distance = offset.length();
+    if distance < f32::EPSILON
+        || distance > a_radius + b_radius
+        || distance < (a_radius - b_radius).abs()
+    {
+        return Vec::new();
+    }
+    let along = (a_radius * a_radius - b_radius * b_radius + distance * distance) / (2. * distance);
+    let across = (a_radius * a_radius - along * along).max(0.).sqrt();
+    let direction = offset / distance;
+    let middle = a_center + direction * along;
+    let normal = Vec3::Y.cross(direction);
+    vec![middle - normal * across, middle + normal * across]
+}
+
+/// Parameters along both segments where they cross, endpoints included.
+pub fn segment_intersection(
+    a_start: Vec3,
+    a_end: Vec3,
+    b_start: Vec3,
+    b_end: Vec3,
+) -> Option<(f32, f32)> {
+    let a = a_end - a_start;
+    let b = b_end - b_start;
+    let denominator = a.x * b.z - a.z * b.x;
+    if denominator.abs() < f32::EPSILON {
+        return None;
+    }
+    let offset = b_start - a_start;
+    let t = (offset.x * b.z - offset.z * b.x) / denominator;
+    let u = (offset.x * a.z - offset.z * a.x) / denominator;
+    ((0. ..=1.).contains(&t) && (0. ..=1.).contains(&u)).then_some((t, u))
+}
+
+/// Even-odd test of `point` against the closed outline through `points`.
+pub fn point_in_polygon(point: Vec3, points: &[Vec3]) -> bool {
+    let mut inside = false;
+    for (a, b) in points.iter().zip(points.iter().cycle().skip(1)) {
+        if (a.z > point.z) != (b.z > point.z)
+            && point.x < a.x + (point.z - a.z) / (b.z - a.z) * (b.x - a.x)
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circle_through_points() {
+        let center = circle_through(vec3(1., 0., 0.), vec3(0., 0., 1.), vec3(-1., 0., 0.));
+        assert!(center.is_some_and(|center| center.distance(Vec3::ZERO) < 1e-5));
+    }
+
+    #[test]
+    fn no_circle_through_collinear_points() {
+        assert_eq!(
+            circle_through(vec3(0., 0., 0.), vec3(1., 0., 1.), vec3(2., 0., 2.)),
+            None
+        );
+    }
+
+    #[test]
+    fn simplify_drops_points_within_tolerance() {
+        let points = [
+            vec3(0., 0., 0.),
+            vec3(1., 0., 0.01),
+            vec3(2., 0., -0.01),
+            vec3(3., 0., 0.),
+        ];
+        assert_eq!(simplify(&points, 0.05), vec![points[0], points[3]]);
+    }
+
+    #[test]
+    fn simplify_keeps_corners() {
+        let points = [
+            vec3(0., 0., 0.),
+            vec3(1., 0., 0.02),
+            vec3(2., 0., 0.),
+            vec3(2., 0., 1.),
+            vec3(2.01, 0., 2.),
+        ];
+        assert_eq!(
+            simplify(&points, 0.05),
+            vec![points[0], points[2], points[4]]
+        );
+    }
+
+    #[test]
+    fn simplify_leaves_short_strokes() {
+        let points = [vec3(0., 0., 0.), vec3(1., 0., 0.)];
+        assert_eq!(simplify(&points, 0.05), points.to_vec());
+    }
+}
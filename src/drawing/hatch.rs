@@ -0,0 +1,321 @@
+use std::f32::consts::{PI, TAU};
+
+use bevy::{asset::RenderAssetUsages, ecs::system::SystemParam, prelude::*};
+use bevy_simple_subsecond_system::*;
+
+use crate::{
+    cursor::Cursor,
+    mesh::triangulate::triangulate_with_holes,
+    reload::{ReloadLevel, Reloadable},
+};
+
+use super::{
+    arc::Arc,
+    boundary::{find_boundary, find_islands},
+    circle::Circle,
+    construction::Construction,
+    draw::{DEFAULT_RESOLUTION, DrawMode},
+    line::Line,
+    rectangle::Rectangle,
+    size::HATCH_SPACING,
+};
+
+/// Most pattern lines a single hatch will generate.
+const MAX_HATCH_LINES: f32 = 2000.;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HatchStyle {
+    Solid,
+    Lines,
+    Crosshatch,
+}
+
+/// Fill pattern; `angle` turns the lines around +Y and `spacing` separates them.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct HatchPattern {
+    pub style: HatchStyle,
+    pub angle: f32,
+    pub spacing: f32,
+}
+
+impl Default for HatchPattern {
+    fn default() -> Self {
+        HatchPattern {
+            style: HatchStyle::Lines,
+            angle: PI / 4.,
+            spacing: HATCH_SPACING,
+        }
+    }
+}
+
+/// Fill of the region around `seed`, leaving out the `islands` of shapes standing inside it.
+/// `boundary`, `islands` and `segments` are recomputed from the surrounding shapes whenever
+/// they change, and are empty while the region is open.
+#[derive(Component, Debug)]
+pub struct Hatch {
+    pub seed: Vec3,
+    pub pattern: HatchPattern,
+    pub boundary: Vec<Vec3>,
+    pub islands: Vec<Vec<Vec3>>,
+    pub segments: Vec<[Vec3; 2]>,
+}
+
+/// Shapes that can bound a hatch. Construction geometry only guides, so it never does.
+#[derive(SystemParam)]
+pub struct BoundaryShapes<'w, 's> {
+    lines: Query<'w, 's, &'static Line, Without<Construction>>,
+    arcs: Query<'w, 's, &'static Arc, Without<Construction>>,
+    circles: Query<'w, 's, &'static Circle, Without<Construction>>,
+    rectangles: Query<'w, 's, &'static Rectangle, Without<Construction>>,
+}
+
+impl BoundaryShapes<'_, '_> {
+    /// Every shape as straight segments, with curves sampled and shared edges kept once.
+    fn segments(&self) -> Vec<[Vec3; 2]> {
+        let mut segments: Vec<[Vec3; 2]> = Vec::new();
+        let mut add = |start: Vec3, end: Vec3| {
+            if start != end
+                && !segments
+                    .iter()
+                    .any(|segment| *segment == [start, end] || *segment == [end, start])
+            {
+                segments.push([start, end]);
+            }
+        };
+
+        for line in self.lines.iter() {
+            add(line.start, line.end);
+        }
+        for arc in self.arcs.iter() {
+            for pair in arc.positions().windows(2) {
+                add(pair[0], pair[1]);
+            }
+        }
+        for circle in self.circles.iter() {
+            let positions: Vec<Vec3> = (0..DEFAULT_RESOLUTION)
+                .map(|index| {
+                    circle.center
+                        + Quat::from_rotation_y(TAU * index as f32 / DEFAULT_RESOLUTION as f32)
+                            * Vec3::X
+                            * circle.radius
+                })
+                .collect();
+            for (index, position) in positions.iter().enumerate() {
+                add(*position, positions[(index + 1) % positions.len()]);
+            }
+        }
+        for rectangle in self.rectangles.iter() {
+            let corners = rectangle.corners();
+            for (index, corner) in corners.iter().enumerate() {
+                add(*corner, corners[(index + 1) % corners.len()]);
+            }
+        }
+        segments
+    }
+}
+
+type ChangedShape = Or<(
+    Changed<Line>,
+    Changed<Arc>,
+    Changed<Circle>,
+    Changed<Rectangle>,
+    Changed<Construction>,
+)>;
+
+/// Whether any shape that can bound a hatch was added, edited or removed this frame.
+#[derive(SystemParam)]
+pub struct BoundaryChanges<'w, 's> {
+    changed: Query<'w, 's, (), ChangedShape>,
+    removed_lines: RemovedComponents<'w, 's, Line>,
+    removed_arcs: RemovedComponents<'w, 's, Arc>,
+    removed_circles: RemovedComponents<'w, 's, Circle>,
+    removed_rectangles: RemovedComponents<'w, 's, Rectangle>,
+    removed_construction: RemovedComponents<'w, 's, Construction>,
+}
+
+impl BoundaryChanges<'_, '_> {
+    fn any(&mut self) -> bool {
+        // Read every reader so none of them report stale removals next frame
+        let removed = [
+            self.removed_lines.read().count(),
+            self.removed_arcs.read().count(),
+            self.removed_circles.read().count(),
+            self.removed_rectangles.read().count(),
+            self.removed_construction.read().count(),
+        ];
+        !self.changed.is_empty() || removed.iter().any(|count| *count > 0)
+    }
+}
+
+pub struct HatchPlugin;
+
+impl Plugin for HatchPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HatchPattern::default())
+            .add_systems(
+                Update,
+                (change_hatch_pattern, handle_draw_hatch).run_if(in_state(DrawMode::Hatch)),
+            )
+            .add_systems(Update, (update_hatches, display_hatches).chain());
+    }
+}
+
+#[hot]
+fn change_hatch_pattern(keyboard: Res<ButtonInput<KeyCode>>, mut pattern: ResMut<HatchPattern>) {
+    if keyboard.just_pressed(KeyCode::Tab) {
+        pattern.style = match pattern.style {
+            HatchStyle::Solid => HatchStyle::Lines,
+            HatchStyle::Lines => HatchStyle::Crosshatch,
+            HatchStyle::Crosshatch => HatchStyle::Solid,
+        };
+    } else if keyboard.any_just_pressed([KeyCode::Equal, KeyCode::NumpadAdd]) {
+        pattern.spacing *= 1.25;
+    } else if keyboard.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        pattern.spacing /= 1.25;
+    } else if keyboard.just_pressed(KeyCode::BracketRight) {
+        pattern.angle += PI / 12.;
+    } else if keyboard.just_pressed(KeyCode::BracketLeft) {
+        pattern.angle -= PI / 12.;
+    } else {
+        return;
+    }
+    info!("Hatch pattern: {:?}", *pattern);
+}
+
+#[hot]
+fn handle_draw_hatch(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    pattern: Res<HatchPattern>,
+    shapes: BoundaryShapes,
+) {
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Create hatch entity if the click is inside a closed region
+    if find_boundary(&shapes.segments(), cursor.position).is_none() {
+        warn!("No closed boundary around {:?}", cursor.position);
+        return;
+    }
+    commands.spawn((
+        Hatch {
+            seed: cursor.position,
+            pattern: *pattern,
+            boundary: Vec::new(),
+            islands: Vec::new(),
+            segments: Vec::new(),
+        },
+        Transform::default(),
+        Reloadable {
+            level: ReloadLevel::Hard,
+        },
+    ));
+}
+
+/// Recomputes new hatches, and every hatch once their surrounding shapes change.
+#[hot]
+fn update_hatches(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut query: Query<(Entity, &mut Hatch)>,
+    shapes: BoundaryShapes,
+    mut changes: BoundaryChanges,
+) {
+    let boundary_changed = changes.any();
+    let mut segments = None;
+
+    for (entity, mut hatch) in query.iter_mut() {
+        if !boundary_changed && !hatch.is_added() {
+            continue;
+        }
+        let segments = segments.get_or_insert_with(|| shapes.segments());
+        hatch.boundary = find_boundary(segments, hatch.seed).unwrap_or_default();
+        hatch.islands = find_islands(segments, &hatch.boundary);
+        let pattern = hatch.pattern;
+        let lines =
+            |angle: f32| hatch_lines(&hatch.boundary, &hatch.islands, angle, pattern.spacing);
+        hatch.segments = match pattern.style {
+            HatchStyle::Solid => Vec::new(),
+            HatchStyle::Lines => lines(pattern.angle),
+            HatchStyle::Crosshatch => {
+                let mut segments = lines(pattern.angle);
+                segments.extend(lines(pattern.angle + PI / 2.));
+                segments
+            }
+        };
+
+        // Solid fills are meshes rather than gizmos
+        if hatch.pattern.style == HatchStyle::Solid {
+            let (positions, indices) = triangulate_with_holes(&hatch.boundary, &hatch.islands);
+            let normals = vec![[0f32, 1f32, 0f32]; positions.len()];
+            let mesh = Mesh::new(
+                bevy::render::mesh::PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            )
+            .with_inserted_indices(bevy::render::mesh::Indices::U32(indices))
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+            commands.entity(entity).insert((
+                Mesh3d(meshes.add(mesh)),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: Color::WHITE,
+                    unlit: true,
+                    ..default()
+                })),
+            ));
+        }
+    }
+}
+
+#[hot]
+fn display_hatches(mut gizmos: Gizmos, query: Query<&Hatch>) {
+    for hatch in query.iter() {
+        for [start, end] in hatch.segments.iter() {
+            gizmos.line(*start, *end, Color::WHITE);
+        }
+    }
+}
+
+/// Parallel lines at `angle` and `spacing`, clipped to the inside of `boundary` and the
+/// outside of `islands`.
+fn hatch_lines(
+    boundary: &[Vec3],
+    islands: &[Vec<Vec3>],
+    angle: f32,
+    spacing: f32,
+) -> Vec<[Vec3; 2]> {
+    let direction = Quat::from_rotation_y(angle) * Vec3::X;
+    let normal = Vec3::Y.cross(direction);
+    let (min, max) = boundary
+        .iter()
+        .map(|point| point.dot(normal))
+        .fold((f32::MAX, f32::MIN), |(min, max), offset| {
+            (min.min(offset), max.max(offset))
+        });
+    if boundary.len() < 3 || (max - min) / spacing > MAX_HATCH_LINES {
+        return Vec::new();
+    }
+
+    let mut segments = Vec::new();
+    let mut offset = (min / spacing).ceil() * spacing;
+    while offset <= max {
+        // Crossings of this line with every outline, paired up inside-to-outside
+        let mut crossings: Vec<Vec3> = std::iter::once(boundary)
+            .chain(islands.iter().map(Vec::as_slice))
+            .flat_map(|outline| outline.iter().zip(outline.iter().cycle().skip(1)))
+            .filter_map(|(a, b)| {
+                let (side_a, side_b) = (a.dot(normal) - offset, b.dot(normal) - offset);
+                ((side_a < 0.) != (side_b < 0.)).then(|| a.lerp(*b, side_a / (side_a - side_b)))
+            })
+            .collect();
+        crossings.sort_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)));
+        for pair in crossings.chunks_exact(2) {
+            segments.push([pair[0], pair[1]]);
+        }
+        offset += spacing;
+    }
+    segments
+}
@@ -6,22 +6,27 @@ use crate::{
     reload::{ReloadLevel, Reloadable},
 };
 
-#[derive(Component, Debug, Default)]
+#[derive(Component, Debug, Default, Clone, Copy)]
 pub struct Line {
     pub start: Vec3,
     pub end: Vec3,
 }
 
 use super::{
+    arc::Arc,
+    construction::Construction,
     dot::Dot,
     draw::{CurrentDrawing, DEFAULT_POS, DrawMode, LineChain, reset_drawing},
+    polyline::Polyline,
+    size::{CLOSE_RADIUS, DOT_RADIUS},
 };
 
 pub struct LinePlugin;
 
 impl Plugin for LinePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, display_lines);
+        app.add_systems(Update, handle_draw_line.run_if(in_state(DrawMode::Line)))
+            .add_systems(Update, display_lines);
     }
 }
 
@@ -29,10 +34,15 @@ impl Plugin for LinePlugin {
 pub fn handle_draw_line(
     mut commands: Commands,
     mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
     cursor: Res<Cursor>,
     mut current_drawing: ResMut<CurrentDrawing>,
     mut line_chain: ResMut<LineChain>,
 ) {
+    if keyboard.just_pressed(KeyCode::Tab) {
+        line_chain.tangent_arc = !line_chain.tangent_arc;
+        info!("Tangent arc: {:?}", line_chain.tangent_arc);
+    }
     if mouse_input.just_pressed(MouseButton::Right) {
         reset_drawing(current_drawing, line_chain);
         return;
@@ -62,23 +72,78 @@ pub fn handle_draw_line(
                     level: ReloadLevel::Hard,
                 },
             ));
+            line_chain.points.push(start);
+            line_chain.polyline = Some(
+                commands
+                    .spawn((
+                        Polyline::default(),
+                        Reloadable {
+                            level: ReloadLevel::Hard,
+                        },
+                    ))
+                    .id(),
+            );
+        }
+        let Some(polyline) = line_chain.polyline else {
+            return;
+        };
+
+        // Close the chain by clicking back on its first point
+        let first = line_chain.points[0];
+        let closed = line_chain.count >= 2 && (end - first).length() <= CLOSE_RADIUS;
+        let end = if closed { first } else { end };
+
+        if !closed {
+            commands.spawn((
+                Dot { position: end },
+                Reloadable {
+                    level: ReloadLevel::Hard,
+                },
+            ));
         }
 
-        commands.spawn((
-            Dot { position: end },
-            Reloadable {
-                level: ReloadLevel::Hard,
-            },
-        ));
-        commands.spawn((
-            Line {
-                start: start,
-                end: end,
-            },
-            Reloadable {
-                level: ReloadLevel::Hard,
-            },
-        ));
+        // Continue tangent to the previous segment, or straight when that is impossible
+        match chain_arc(&line_chain, start, end) {
+            Some(arc) => {
+                let positions = arc.positions();
+                let last = if closed {
+                    positions.len() - 1
+                } else {
+                    positions.len()
+                };
+                line_chain.points.extend_from_slice(&positions[1..last]);
+                line_chain.direction = arc.end_direction();
+                commands.spawn((
+                    arc,
+                    Reloadable {
+                        level: ReloadLevel::Hard,
+                    },
+                    ChildOf(polyline),
+                ));
+            }
+            None => {
+                if !closed {
+                    line_chain.points.push(end);
+                }
+                line_chain.direction = (end - start).normalize_or_zero();
+                commands.spawn((
+                    Line { start, end },
+                    Reloadable {
+                        level: ReloadLevel::Hard,
+                    },
+                    ChildOf(polyline),
+                ));
+            }
+        }
+        commands.entity(polyline).insert(Polyline {
+            points: line_chain.points.clone(),
+            closed,
+        });
+
+        if closed {
+            reset_drawing(current_drawing, line_chain);
+            return;
+        }
         current_drawing.position[0] = end;
         current_drawing.position[1] = DEFAULT_POS;
         line_chain.count += 1;
@@ -88,17 +153,47 @@ pub fn handle_draw_line(
 #[hot]
 fn display_lines(
     mut gizmos: Gizmos,
-    query: Query<&Line>,
+    query: Query<&Line, Without<Construction>>,
     cursor: Res<Cursor>,
     state: Res<State<DrawMode>>,
     current_drawing: ResMut<CurrentDrawing>,
+    line_chain: Res<LineChain>,
 ) {
     // Display existing lines
     for line in query.iter() {
         gizmos.line(line.start, line.end, Color::WHITE);
     }
-    // Display currently drawn line
+    // Display currently drawn line or tangent arc
     if state.get() == &DrawMode::Line && current_drawing.position[0] != DEFAULT_POS {
-        gizmos.line(current_drawing.position[0], cursor.position, Color::WHITE);
+        match chain_arc(&line_chain, current_drawing.position[0], cursor.position) {
+            Some(arc) => {
+                gizmos.linestrip(arc.positions(), Color::WHITE);
+            }
+            None => {
+                gizmos.line(current_drawing.position[0], cursor.position, Color::WHITE);
+            }
+        }
+
+        // Highlight the first point when the next click would close the chain
+        if line_chain.count >= 2
+            && (cursor.position - line_chain.points[0]).length() <= CLOSE_RADIUS
+        {
+            gizmos.circle(
+                Isometry3d::new(
+                    line_chain.points[0],
+                    Quat::from_rotation_arc(Vec3::Z, Dir3::Y.as_vec3()),
+                ),
+                DOT_RADIUS * 2.,
+                Color::WHITE,
+            );
+        }
+    }
+}
+
+/// Tangent arc for the next segment, when tangent arcs are on and the chain has a direction.
+fn chain_arc(line_chain: &LineChain, start: Vec3, end: Vec3) -> Option<Arc> {
+    if !line_chain.tangent_arc || line_chain.count == 0 {
+        return None;
     }
+    Arc::tangent(start, line_chain.direction, end)
 }
@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use bevy_simple_subsecond_system::*;
+
+use crate::cursor::Cursor;
+
+use super::{
+    draw::{CurrentDrawing, DEFAULT_POS, DrawMode, reset_current_drawing},
+    geometry::reflect,
+    select::SelectedShapes,
+};
+
+/// Whether mirroring keeps the selected shapes next to their mirror images.
+#[derive(Resource, Debug, PartialEq)]
+pub struct MirrorOptions {
+    pub keep_originals: bool,
+}
+
+impl Default for MirrorOptions {
+    fn default() -> Self {
+        MirrorOptions {
+            keep_originals: true,
+        }
+    }
+}
+
+pub struct MirrorPlugin;
+
+impl Plugin for MirrorPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MirrorOptions::default())
+            .add_systems(
+                Update,
+                handle_draw_mirror.run_if(in_state(DrawMode::Mirror)),
+            )
+            .add_systems(Update, display_mirror_preview);
+    }
+}
+
+#[hot]
+fn handle_draw_mirror(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+    mut options: ResMut<MirrorOptions>,
+    mut selection: SelectedShapes,
+) {
+    if keyboard.just_pressed(KeyCode::Tab) {
+        options.keep_originals = !options.keep_originals;
+        info!("Keep originals: {:?}", options.keep_originals);
+    }
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    // Shift-clicks pick the selection instead
+    if !mouse_input.just_pressed(MouseButton::Left) || keyboard.pressed(KeyCode::ShiftLeft) {
+        return;
+    }
+    if selection.is_empty() {
+        warn!("Shift-click shapes to mirror first");
+        return;
+    }
+
+    // Define start of mirror axis
+    if current_drawing.position[0] == DEFAULT_POS {
+        current_drawing.position[0] = cursor.position;
+    }
+    // Define end of mirror axis
+    else if current_drawing.position[1] == DEFAULT_POS {
+        current_drawing.position[1] = cursor.position;
+    }
+
+    let start = current_drawing.position[0];
+    let end = current_drawing.position[1];
+
+    // Mirror the selection once the axis is defined
+    if start != DEFAULT_POS && end != DEFAULT_POS {
+        if start != end {
+            let map = |point| reflect(point, start, end);
+            if options.keep_originals {
+                selection.copy(map, true);
+            } else {
+                selection.apply(map, true);
+            }
+            selection.clear();
+        }
+        reset_current_drawing(current_drawing);
+    }
+}
+
+#[hot]
+fn display_mirror_preview(
+    mut gizmos: Gizmos,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    current_drawing: Res<CurrentDrawing>,
+) {
+    // Display mirror axis
+    if state.get() == &DrawMode::Mirror && current_drawing.position[0] != DEFAULT_POS {
+        gizmos.line(current_drawing.position[0], cursor.position, Color::WHITE);
+    }
+}
@@ -1,7 +1,29 @@
 pub mod arc;
+pub mod array;
+pub mod bezier;
+pub mod boundary;
+pub mod chamfer;
 pub mod circle;
+pub mod construction;
+pub mod dimension;
+pub mod divide;
 pub mod dot;
 pub mod draw;
+pub mod ellipse;
+pub mod fillet;
+pub mod freehand;
+pub mod geometry;
+pub mod hatch;
 pub mod line;
+pub mod mirror;
+pub mod offset;
+pub mod pick;
+pub mod polygon;
+pub mod polyline;
 pub mod rectangle;
+pub mod select;
 pub mod size;
+pub mod slot;
+pub mod spline;
+pub mod text;
+pub mod trim;
@@ -0,0 +1,685 @@
+use std::{collections::VecDeque, f32::consts::TAU};
+
+use bevy::{
+    ecs::system::SystemParam,
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+};
+use bevy_simple_subsecond_system::*;
+
+use crate::{
+    cursor::Cursor,
+    reload::{ReloadLevel, Reloadable},
+};
+
+use super::{
+    arc::Arc,
+    circle::Circle,
+    dot::Dot,
+    draw::{CurrentDrawing, DEFAULT_POS, DrawMode, reset_current_drawing},
+    geometry::{
+        circle_intersections, line_circle_intersections, line_intersection, plane_angle,
+        signed_angle,
+    },
+    line::Line,
+    pick::{pick_arc, pick_circle, pick_line},
+    polyline::Polyline,
+    size::MERGE_DISTANCE,
+};
+
+/// Corners whose joint would reach further than this many offset distances get rounded instead.
+const MITER_LIMIT: f32 = 4.;
+
+/// Distance typed while offsetting. Empty means the cursor picks the distance.
+#[derive(Resource, Default, Debug, PartialEq)]
+pub struct OffsetDistance {
+    pub typed: String,
+}
+
+impl OffsetDistance {
+    pub fn distance(&self) -> Option<f32> {
+        self.typed
+            .parse::<f32>()
+            .ok()
+            .filter(|distance| *distance > 0.)
+    }
+}
+
+/// Straight or curved piece of a chain, oriented along the chain.
+#[derive(Debug, Clone, Copy)]
+pub enum Segment {
+    Line(Line),
+    Arc(Arc),
+}
+
+impl Segment {
+    pub fn start(&self) -> Vec3 {
+        match self {
+            Segment::Line(line) => line.start,
+            Segment::Arc(arc) => arc.start,
+        }
+    }
+
+    pub fn end(&self) -> Vec3 {
+        match self {
+            Segment::Line(line) => line.end,
+            Segment::Arc(arc) => arc.end,
+        }
+    }
+
+    pub fn length(&self) -> f32 {
+        match self {
+            Segment::Line(line) => line.start.distance(line.end),
+            Segment::Arc(arc) => arc.length(),
+        }
+    }
+
+    pub fn reversed(&self) -> Self {
+        match *self {
+            Segment::Line(line) => Segment::Line(Line {
+                start: line.end,
+                end: line.start,
+            }),
+            Segment::Arc(arc) => Segment::Arc(Arc {
+                start: arc.end,
+                end: arc.start,
+                sweep: -arc.sweep,
+                ..arc
+            }),
+        }
+    }
+
+    /// Distance from `point` to the line or circle the segment lies on.
+    pub fn distance_to(&self, point: Vec3) -> f32 {
+        match self {
+            Segment::Line(line) => {
+                let normal = Vec3::Y.cross(line.end - line.start).normalize_or_zero();
+                (point - line.start).dot(normal).abs()
+            }
+            Segment::Arc(arc) => ((point - arc.center).length() - arc.radius()).abs(),
+        }
+    }
+
+    /// Whether `point` lies to the left of the direction of travel.
+    pub fn is_left(&self, point: Vec3) -> bool {
+        match self {
+            Segment::Line(line) => {
+                (point - line.start).dot(Vec3::Y.cross(line.end - line.start)) > 0.
+            }
+            // Positive sweeps turn left, so their inside is on the left
+            Segment::Arc(arc) => ((point - arc.center).length() < arc.radius()) == (arc.sweep > 0.),
+        }
+    }
+
+    /// Copy moved `distance` to the left of travel, or `None` when an arc would shrink away.
+    pub fn offset(&self, distance: f32) -> Option<Self> {
+        match *self {
+            Segment::Line(line) => {
+                let normal = Vec3::Y.cross(line.end - line.start).normalize_or_zero();
+                Some(Segment::Line(Line {
+                    start: line.start + normal * distance,
+                    end: line.end + normal * distance,
+                }))
+            }
+            Segment::Arc(arc) => {
+                let radius = arc.radius() - distance * arc.sweep.signum();
+                (radius > 0.).then(|| {
+                    Segment::Arc(Arc {
+                        start: arc.center + (arc.start - arc.center).normalize() * radius,
+                        end: arc.center + (arc.end - arc.center).normalize() * radius,
+                        ..arc
+                    })
+                })
+            }
+        }
+    }
+
+    /// Same segment moved to begin at `point`, which has to lie on its line or circle.
+    pub fn with_start(&self, point: Vec3) -> Self {
+        match *self {
+            Segment::Line(line) => Segment::Line(Line {
+                start: point,
+                ..line
+            }),
+            Segment::Arc(arc) => Segment::Arc(Arc {
+                start: point,
+                sweep: arc.sweep - signed_angle(arc.start - arc.center, point - arc.center),
+                ..arc
+            }),
+        }
+    }
+
+    /// Same segment moved to finish at `point`, which has to lie on its line or circle.
+    pub fn with_end(&self, point: Vec3) -> Self {
+        match *self {
+            Segment::Line(line) => Segment::Line(Line { end: point, ..line }),
+            Segment::Arc(arc) => Segment::Arc(Arc {
+                end: point,
+                sweep: arc.sweep + signed_angle(arc.end - arc.center, point - arc.center),
+                ..arc
+            }),
+        }
+    }
+
+    /// Where `point` falls along the segment, from 0 at the start to 1 at the end.
+    /// Points on an arc's circle but outside its sweep come out above 1.
+    pub fn parameter(&self, point: Vec3) -> f32 {
+        match self {
+            Segment::Line(line) => {
+                let direction = line.end - line.start;
+                (point - line.start).dot(direction) / direction.length_squared()
+            }
+            Segment::Arc(arc) => {
+                let angle = plane_angle(point - arc.center) - plane_angle(arc.start - arc.center);
+                (angle * arc.sweep.signum()).rem_euclid(TAU) / arc.sweep.abs()
+            }
+        }
+    }
+
+    pub fn point_at(&self, parameter: f32) -> Vec3 {
+        match self {
+            Segment::Line(line) => line.start.lerp(line.end, parameter),
+            Segment::Arc(arc) => {
+                arc.center + Quat::from_rotation_y(arc.sweep * parameter) * (arc.start - arc.center)
+            }
+        }
+    }
+
+    /// Part of the segment between two parameters, which may reach past its ends.
+    pub fn between(&self, from: f32, to: f32) -> Self {
+        match self {
+            Segment::Line(_) => Segment::Line(Line {
+                start: self.point_at(from),
+                end: self.point_at(to),
+            }),
+            Segment::Arc(arc) => Segment::Arc(Arc::new(
+                arc.center,
+                self.point_at(from),
+                arc.sweep * (to - from),
+            )),
+        }
+    }
+
+    /// Where the lines or circles the two segments lie on cross.
+    pub fn intersections(&self, other: &Segment) -> Vec<Vec3> {
+        match (self, other) {
+            (Segment::Line(a), Segment::Line(b)) => {
+                line_intersection(a.start, a.end, b.start, b.end)
+                    .into_iter()
+                    .collect()
+            }
+            (Segment::Line(line), Segment::Arc(arc)) | (Segment::Arc(arc), Segment::Line(line)) => {
+                line_circle_intersections(line.start, line.end, arc.center, arc.radius())
+            }
+            (Segment::Arc(a), Segment::Arc(b)) => {
+                circle_intersections(a.center, a.radius(), b.center, b.radius())
+            }
+        }
+    }
+}
+
+/// What the offset tool copies.
+#[derive(Debug)]
+pub enum OffsetSource {
+    Circle {
+        center: Vec3,
+        radius: f32,
+    },
+    /// Connected segments, with the one that was picked.
+    Chain {
+        segments: Vec<Segment>,
+        closed: bool,
+        picked: Segment,
+    },
+}
+
+/// Shapes the offset tool can copy, with the polylines or rectangles owning them.
+#[derive(SystemParam)]
+pub struct OffsetSources<'w, 's> {
+    lines: Query<'w, 's, (Entity, &'static Line, Option<&'static ChildOf>)>,
+    arcs: Query<'w, 's, (Entity, &'static Arc, Option<&'static ChildOf>)>,
+    circles: Query<'w, 's, (Entity, &'static Circle)>,
+    children: Query<'w, 's, &'static Children>,
+}
+
+impl OffsetSources<'_, '_> {
+    /// Shape under `point`, widened to the whole chain when it belongs to one.
+    pub fn pick(&self, point: Vec3) -> Option<OffsetSource> {
+        let (picked, owner) = if let Some((entity, line)) = pick_line(
+            self.lines.iter().map(|(entity, line, _)| (entity, line)),
+            point,
+        ) {
+            (Segment::Line(*line), self.lines.get(entity).ok()?.2)
+        } else if let Some((entity, arc)) = pick_arc(
+            self.arcs.iter().map(|(entity, arc, _)| (entity, arc)),
+            point,
+        ) {
+            (Segment::Arc(*arc), self.arcs.get(entity).ok()?.2)
+        } else {
+            let (_, circle) = pick_circle(self.circles.iter(), point)?;
+            return Some(OffsetSource::Circle {
+                center: circle.center,
+                radius: circle.radius,
+            });
+        };
+
+        let Some(children) = owner.and_then(|owner| self.children.get(owner.parent()).ok()) else {
+            return Some(OffsetSource::Chain {
+                segments: vec![picked],
+                closed: false,
+                picked,
+            });
+        };
+        let pieces = children
+            .iter()
+            .filter_map(|child| {
+                if let Ok((_, line, _)) = self.lines.get(child) {
+                    Some(Segment::Line(*line))
+                } else {
+                    self.arcs
+                        .get(child)
+                        .ok()
+                        .map(|(_, arc, _)| Segment::Arc(*arc))
+                }
+            })
+            .collect();
+        let (segments, closed) = connect(pieces);
+
+        // Sides are judged along the chain, which may run against the picked segment
+        let picked = segments
+            .iter()
+            .find(|segment| segment.end() == picked.start() && segment.start() == picked.end())
+            .copied()
+            .unwrap_or(picked);
+        Some(OffsetSource::Chain {
+            segments,
+            closed,
+            picked,
+        })
+    }
+}
+
+pub struct OffsetPlugin;
+
+impl Plugin for OffsetPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(OffsetDistance::default())
+            .add_systems(OnEnter(DrawMode::Offset), clear_offset_distance)
+            .add_systems(
+                Update,
+                (type_offset_distance, handle_draw_offset).run_if(in_state(DrawMode::Offset)),
+            )
+            .add_systems(Update, display_offset_preview);
+    }
+}
+
+/// Each offset starts out following the cursor again.
+#[hot]
+fn clear_offset_distance(mut offset_distance: ResMut<OffsetDistance>) {
+    offset_distance.typed.clear();
+}
+
+#[hot]
+fn type_offset_distance(
+    mut keyboard_input: EventReader<KeyboardInput>,
+    mut offset_distance: ResMut<OffsetDistance>,
+) {
+    for input in keyboard_input.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
+        match &input.logical_key {
+            Key::Backspace => {
+                offset_distance.typed.pop();
+            }
+            Key::Escape => {
+                offset_distance.typed.clear();
+            }
+            Key::Character(characters)
+                if characters.chars().all(|c| c.is_ascii_digit() || c == '.') =>
+            {
+                offset_distance.typed.push_str(characters);
+            }
+            _ => continue,
+        }
+        info!("Offset distance: {:?}", offset_distance.typed);
+    }
+}
+
+#[hot]
+fn handle_draw_offset(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+    mut offset_distance: ResMut<OffsetDistance>,
+    sources: OffsetSources,
+) {
+    if mouse_input.just_pressed(MouseButton::Right) {
+        offset_distance.typed.clear();
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Pick shape to copy
+    if current_drawing.position[0] == DEFAULT_POS {
+        if sources.pick(cursor.position).is_some() {
+            current_drawing.position[0] = cursor.position;
+        }
+        return;
+    }
+
+    // Pick side, and distance unless one was typed
+    let Some(source) = sources.pick(current_drawing.position[0]) else {
+        reset_current_drawing(current_drawing);
+        return;
+    };
+    match offset(&source, cursor.position, offset_distance.distance()) {
+        Some(OffsetSource::Circle { center, radius }) => {
+            commands.spawn((
+                Circle { center, radius },
+                Reloadable {
+                    level: ReloadLevel::Hard,
+                },
+            ));
+        }
+        Some(OffsetSource::Chain {
+            segments, closed, ..
+        }) => spawn_chain(&mut commands, segments, closed),
+        None => {
+            warn!("Offset collapses the shape");
+            return;
+        }
+    }
+    offset_distance.typed.clear();
+    reset_current_drawing(current_drawing);
+}
+
+#[hot]
+fn display_offset_preview(
+    mut gizmos: Gizmos,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    current_drawing: Res<CurrentDrawing>,
+    offset_distance: Res<OffsetDistance>,
+    sources: OffsetSources,
+) {
+    if state.get() != &DrawMode::Offset || current_drawing.position[0] == DEFAULT_POS {
+        return;
+    }
+    let Some(source) = sources.pick(current_drawing.position[0]) else {
+        return;
+    };
+
+    // Display the copy the next click would create
+    match offset(&source, cursor.position, offset_distance.distance()) {
+        Some(OffsetSource::Circle { center, radius }) => {
+            gizmos.circle(
+                Isometry3d::new(center, Quat::from_rotation_arc(Vec3::Z, Vec3::Y)),
+                radius,
+                Color::WHITE,
+            );
+        }
+        Some(OffsetSource::Chain { segments, .. }) => {
+            for segment in segments {
+                match segment {
+                    Segment::Line(line) => gizmos.line(line.start, line.end, Color::WHITE),
+                    Segment::Arc(arc) => gizmos.linestrip(arc.positions(), Color::WHITE),
+                }
+            }
+        }
+        None => {}
+    }
+}
+
+/// Copy of `source` on the side of `side`, at `distance` or reaching `side` when none is given.
+fn offset(source: &OffsetSource, side: Vec3, distance: Option<f32>) -> Option<OffsetSource> {
+    match source {
+        OffsetSource::Circle { center, radius } => {
+            let outside = (side - *center).length() > *radius;
+            let distance = distance.unwrap_or(((side - *center).length() - radius).abs());
+            let radius = if outside {
+                radius + distance
+            } else {
+                radius - distance
+            };
+            (radius > 0.).then_some(OffsetSource::Circle {
+                center: *center,
+                radius,
+            })
+        }
+        OffsetSource::Chain {
+            segments,
+            closed,
+            picked,
+        } => {
+            let distance = distance.unwrap_or(picked.distance_to(side));
+            let distance = if picked.is_left(side) {
+                distance
+            } else {
+                -distance
+            };
+            Some(OffsetSource::Chain {
+                segments: offset_chain(segments, *closed, distance)?,
+                closed: *closed,
+                picked: picked.offset(distance)?,
+            })
+        }
+    }
+}
+
+/// Orders `pieces` end to start from the first one, turning them around where needed.
+/// Pieces not connected to the first one are left out.
+pub fn connect(mut pieces: Vec<Segment>) -> (Vec<Segment>, bool) {
+    if pieces.is_empty() {
+        return (Vec::new(), false);
+    }
+    let meets = |a: Vec3, b: Vec3| a.distance(b) <= MERGE_DISTANCE;
+    let mut chain = VecDeque::from([pieces.swap_remove(0)]);
+
+    // Walk forward from the last end, then backward from the first start
+    while let Some(index) = pieces.iter().position(|piece| {
+        let end = chain[chain.len() - 1].end();
+        meets(piece.start(), end) || meets(piece.end(), end)
+    }) {
+        let piece = pieces.swap_remove(index);
+        if meets(piece.start(), chain[chain.len() - 1].end()) {
+            chain.push_back(piece);
+        } else {
+            chain.push_back(piece.reversed());
+        }
+    }
+    while let Some(index) = pieces.iter().position(|piece| {
+        let start = chain[0].start();
+        meets(piece.start(), start) || meets(piece.end(), start)
+    }) {
+        let piece = pieces.swap_remove(index);
+        if meets(piece.end(), chain[0].start()) {
+            chain.push_front(piece);
+        } else {
+            chain.push_front(piece.reversed());
+        }
+    }
+
+    let closed = chain.len() > 1 && meets(chain[chain.len() - 1].end(), chain[0].start());
+    (chain.into(), closed)
+}
+
+/// Every segment moved `distance` to the left, with corners mitered where the moved
+/// segments cross near the original corner, and rounded otherwise.
+pub fn offset_chain(segments: &[Segment], closed: bool, distance: f32) -> Option<Vec<Segment>> {
+    let mut moved = segments
+        .iter()
+        .map(|segment| segment.offset(distance))
+        .collect::<Option<Vec<_>>>()?;
+    let count = moved.len();
+    let joints = if closed {
+        count
+    } else {
+        count.saturating_sub(1)
+    };
+
+    let mut rounds = vec![None; count];
+    for index in 0..joints {
+        let next = (index + 1) % count;
+        let (before, after) = (moved[index], moved[next]);
+        if before.end().distance(after.start()) <= MERGE_DISTANCE {
+            continue;
+        }
+        let corner = segments[index].end();
+        let miter = before
+            .intersections(&after)
+            .into_iter()
+            .filter(|point| point.distance(corner) <= distance.abs() * MITER_LIMIT)
+            .min_by(|a, b| a.distance(corner).total_cmp(&b.distance(corner)));
+        match miter {
+            Some(point) => {
+                moved[index] = before.with_end(point);
+                moved[next] = after.with_start(point);
+            }
+            None => {
+                rounds[index] = Some(Segment::Arc(Arc {
+                    center: corner,
+                    start: before.end(),
+                    end: after.start(),
+                    sweep: signed_angle(before.end() - corner, after.start() - corner),
+                }));
+            }
+        }
+    }
+
+    Some(
+        moved
+            .into_iter()
+            .zip(rounds)
+            .flat_map(|(segment, round)| [Some(segment), round])
+            .flatten()
+            .collect(),
+    )
+}
+
+/// Spawns `segments` on their own, or under a new `Polyline` when there are several,
+/// with a `Dot` wherever a line ends.
+pub fn spawn_chain(commands: &mut Commands, segments: Vec<Segment>, closed: bool) {
+    let mut ends: Vec<Vec3> = Vec::new();
+    let mut points: Vec<Vec3> = Vec::new();
+    for segment in segments.iter() {
+        match segment {
+            Segment::Line(line) => {
+                ends.extend([line.start, line.end]);
+                points.push(line.start);
+            }
+            Segment::Arc(arc) => {
+                let positions = arc.positions();
+                points.extend_from_slice(&positions[..positions.len() - 1]);
+            }
+        }
+    }
+    if let (false, Some(last)) = (closed, segments.last()) {
+        points.push(last.end());
+    }
+    ends.dedup_by(|a, b| a.distance(*b) <= MERGE_DISTANCE);
+    if closed && ends.len() > 1 && ends[0].distance(ends[ends.len() - 1]) <= MERGE_DISTANCE {
+        ends.pop();
+    }
+    for position in ends {
+        commands.spawn((
+            Dot { position },
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+        ));
+    }
+
+    let owner = (segments.len() > 1).then(|| {
+        commands
+            .spawn((
+                Polyline { points, closed },
+                Reloadable {
+                    level: ReloadLevel::Hard,
+                },
+            ))
+            .id()
+    });
+    for segment in segments {
+        let mut entity = match segment {
+            Segment::Line(line) => commands.spawn(line),
+            Segment::Arc(arc) => commands.spawn(arc),
+        };
+        entity.insert(Reloadable {
+            level: ReloadLevel::Hard,
+        });
+        if let Some(owner) = owner {
+            entity.insert(ChildOf(owner));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(start: Vec3, end: Vec3) -> Segment {
+        Segment::Line(Line { start, end })
+    }
+
+    #[test]
+    fn square_corner_is_mitered() {
+        let chain = [
+            line(Vec3::ZERO, vec3(2., 0., 0.)),
+            line(vec3(2., 0., 0.), vec3(2., 0., 2.)),
+        ];
+        let moved = offset_chain(&chain, false, 0.5).unwrap();
+        assert_eq!(moved.len(), 2);
+        let miter = vec3(2.5, 0., -0.5);
+        assert!(moved[0].end().distance(miter) < 1e-5);
+        assert!(moved[1].start().distance(miter) < 1e-5);
+        assert!(moved[0].start().distance(vec3(0., 0., -0.5)) < 1e-5);
+        assert!(moved[1].end().distance(vec3(2.5, 0., 2.)) < 1e-5);
+    }
+
+    #[test]
+    fn closed_chain_miters_every_corner() {
+        let corners = [
+            Vec3::ZERO,
+            vec3(0., 0., 2.),
+            vec3(2., 0., 2.),
+            vec3(2., 0., 0.),
+        ];
+        let chain: Vec<_> = (0..4)
+            .map(|index| line(corners[index], corners[(index + 1) % 4]))
+            .collect();
+        let moved = offset_chain(&chain, true, 0.5).unwrap();
+        assert_eq!(moved.len(), 4);
+        for (index, segment) in moved.iter().enumerate() {
+            assert!(segment.end().distance(moved[(index + 1) % 4].start()) < 1e-5);
+        }
+        // Travelling this way round, the left side is the inside
+        assert!(moved[0].start().distance(vec3(0.5, 0., 0.5)) < 1e-5);
+    }
+
+    #[test]
+    fn sharp_corner_is_rounded() {
+        let corner = vec3(2., 0., 0.);
+        let chain = [line(Vec3::ZERO, corner), line(corner, vec3(0., 0., 0.2))];
+        let moved = offset_chain(&chain, false, 0.5).unwrap();
+        assert_eq!(moved.len(), 3);
+        let Segment::Arc(round) = moved[1] else {
+            panic!("expected a round join, got {:?}", moved[1]);
+        };
+        assert!(round.center.distance(corner) < 1e-5);
+        assert!((round.radius() - 0.5).abs() < 1e-5);
+        assert!(round.start.distance(moved[0].end()) < 1e-5);
+        assert!(round.end.distance(moved[2].start()) < 1e-5);
+        // The round goes around the outside of the corner
+        let positions = round.positions();
+        let middle = positions[positions.len() / 2];
+        assert!(middle.x > corner.x + 0.4);
+    }
+}
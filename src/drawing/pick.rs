@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+
+use super::{
+    arc::Arc, circle::Circle, dot::Dot, geometry::distance_to_segment, line::Line,
+    size::PICK_RADIUS,
+};
+
+/// Closest `Dot` within `PICK_RADIUS` of `point`.
+pub fn pick_dot<'a>(
+    dots: impl IntoIterator<Item = (Entity, &'a Dot)>,
+    point: Vec3,
+) -> Option<(Entity, &'a Dot)> {
+    dots.into_iter()
+        .map(|(entity, dot)| ((dot.position - point).length(), entity, dot))
+        .filter(|(distance, _, _)| *distance <= PICK_RADIUS)
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, entity, dot)| (entity, dot))
+}
+
+/// Closest `Line` within `PICK_RADIUS` of `point`.
+pub fn pick_line<'a>(
+    lines: impl IntoIterator<Item = (Entity, &'a Line)>,
+    point: Vec3,
+) -> Option<(Entity, &'a Line)> {
+    lines
+        .into_iter()
+        .map(|(entity, line)| {
+            (
+                distance_to_segment(point, line.start, line.end),
+                entity,
+                line,
+            )
+        })
+        .filter(|(distance, _, _)| *distance <= PICK_RADIUS)
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, entity, line)| (entity, line))
+}
+
+/// Closest `Circle` whose outline passes within `PICK_RADIUS` of `point`.
+pub fn pick_circle<'a>(
+    circles: impl IntoIterator<Item = (Entity, &'a Circle)>,
+    point: Vec3,
+) -> Option<(Entity, &'a Circle)> {
+    circles
+        .into_iter()
+        .map(|(entity, circle)| {
+            let distance = ((point - circle.center).length() - circle.radius).abs();
+            (distance, entity, circle)
+        })
+        .filter(|(distance, _, _)| *distance <= PICK_RADIUS)
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, entity, circle)| (entity, circle))
+}
+
+/// Closest curve, given as points along it, that passes within `PICK_RADIUS` of `point`.
+pub fn pick_curve(
+    curves: impl IntoIterator<Item = (Entity, Vec<Vec3>)>,
+    point: Vec3,
+) -> Option<Entity> {
+    curves
+        .into_iter()
+        .map(|(entity, positions)| {
+            let distance = positions
+                .windows(2)
+                .map(|pair| distance_to_segment(point, pair[0], pair[1]))
+                .fold(f32::INFINITY, f32::min);
+            (distance, entity)
+        })
+        .filter(|(distance, _)| *distance <= PICK_RADIUS)
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, entity)| entity)
+}
+
+/// Closest `Arc` whose outline passes within `PICK_RADIUS` of `point`.
+pub fn pick_arc<'a>(
+    arcs: impl IntoIterator<Item = (Entity, &'a Arc)>,
+    point: Vec3,
+) -> Option<(Entity, &'a Arc)> {
+    arcs.into_iter()
+        .filter(|(_, arc)| arc.contains_angle(point))
+        .map(|(entity, arc)| {
+            let distance = ((point - arc.center).length() - arc.radius()).abs();
+            (distance, entity, arc)
+        })
+        .filter(|(distance, _, _)| *distance <= PICK_RADIUS)
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, entity, arc)| (entity, arc))
+}
@@ -0,0 +1,243 @@
+use std::f32::consts::TAU;
+
+use bevy::{input::mouse::AccumulatedMouseScroll, prelude::*};
+use bevy_simple_subsecond_system::*;
+
+use crate::{
+    cursor::Cursor,
+    reload::{ReloadLevel, Reloadable},
+};
+
+use super::{
+    dot::Dot,
+    draw::{CurrentDrawing, DEFAULT_POS, DrawMode, reset_current_drawing},
+    line::Line,
+};
+
+pub const DEFAULT_SIDES: u32 = 6;
+pub const MIN_SIDES: u32 = 3;
+pub const MAX_SIDES: u32 = 64;
+/// Pixels between the cursor and the side count shown next to it.
+const SIDES_LABEL_OFFSET: Vec2 = vec2(16., 16.);
+
+#[derive(Component, Debug)]
+pub struct Polygon {
+    pub center: Vec3,
+    pub vertex: Vec3,
+    pub sides: u32,
+}
+
+#[derive(Resource, Debug, PartialEq)]
+pub struct PolygonSides {
+    pub count: u32,
+}
+
+impl Default for PolygonSides {
+    fn default() -> Self {
+        PolygonSides {
+            count: DEFAULT_SIDES,
+        }
+    }
+}
+
+/// UI label following the cursor in polygon mode with the current side count.
+#[derive(Component, Debug)]
+struct PolygonSidesLabel;
+
+pub struct PolygonPlugin;
+
+impl Plugin for PolygonPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PolygonSides::default())
+            .add_systems(Startup, spawn_sides_label)
+            .add_systems(
+                Update,
+                (
+                    change_polygon_sides,
+                    handle_draw_polygon.run_if(in_state(DrawMode::Polygon)),
+                    display_polygons,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[hot]
+pub fn handle_draw_polygon(
+    commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+    polygon_sides: Res<PolygonSides>,
+) {
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Define center of polygon
+    if current_drawing.position[0] == DEFAULT_POS {
+        current_drawing.position[0] = cursor.position;
+    }
+    // Define first vertex
+    else if current_drawing.position[1] == DEFAULT_POS {
+        current_drawing.position[1] = cursor.position;
+    }
+
+    let center = current_drawing.position[0];
+    let vertex = current_drawing.position[1];
+
+    // Create line and dots entities if both center and vertex are defined
+    if center != DEFAULT_POS && vertex != DEFAULT_POS {
+        spawn_polygon(
+            commands,
+            Polygon {
+                center,
+                vertex,
+                sides: polygon_sides.count,
+            },
+        );
+        reset_current_drawing(current_drawing);
+    }
+}
+
+#[hot]
+fn change_polygon_sides(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse_scroll: Res<AccumulatedMouseScroll>,
+    state: Res<State<DrawMode>>,
+    mut polygon_sides: ResMut<PolygonSides>,
+) {
+    if state.get() != &DrawMode::Polygon {
+        return;
+    }
+
+    let count = if keyboard.any_just_pressed([KeyCode::Equal, KeyCode::NumpadAdd])
+        || mouse_scroll.delta.y > 0.
+    {
+        (polygon_sides.count + 1).min(MAX_SIDES)
+    } else if keyboard.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract])
+        || mouse_scroll.delta.y < 0.
+    {
+        (polygon_sides.count - 1).max(MIN_SIDES)
+    } else {
+        return;
+    };
+
+    if count != polygon_sides.count {
+        polygon_sides.count = count;
+        info!("Polygon sides: {:?}", count);
+    }
+}
+
+fn spawn_sides_label(mut commands: Commands) {
+    commands.spawn((
+        PolygonSidesLabel,
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        Visibility::Hidden,
+    ));
+}
+
+#[hot]
+fn display_polygons(
+    mut gizmos: Gizmos,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    current_drawing: ResMut<CurrentDrawing>,
+    polygon_sides: Res<PolygonSides>,
+    camera_query: Single<(&Camera, &GlobalTransform)>,
+    label_query: Single<(&mut Text, &mut Node, &mut Visibility), With<PolygonSidesLabel>>,
+) {
+    // Show the side count next to the cursor whenever the next click places a polygon
+    let (camera, camera_transform) = *camera_query;
+    let (mut label, mut node, mut visibility) = label_query.into_inner();
+    let at = camera
+        .world_to_viewport(camera_transform, cursor.position)
+        .ok()
+        .filter(|_| state.get() == &DrawMode::Polygon);
+    visibility.set_if_neq(if at.is_some() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+    if let Some(at) = at {
+        let content = format!("{} sides", polygon_sides.count);
+        if label.0 != content {
+            label.0 = content;
+        }
+        let (left, top) = (
+            Val::Px(at.x + SIDES_LABEL_OFFSET.x),
+            Val::Px(at.y + SIDES_LABEL_OFFSET.y),
+        );
+        if node.left != left || node.top != top {
+            node.left = left;
+            node.top = top;
+        }
+    }
+
+    // Display currently drawn polygon
+    let center = current_drawing.position[0];
+    if state.get() != &DrawMode::Polygon || center == DEFAULT_POS {
+        return;
+    }
+
+    let positions = polygon_vertices(center, cursor.position, polygon_sides.count);
+    for (index, position) in positions.iter().enumerate() {
+        gizmos.line(
+            *position,
+            positions[(index + 1) % positions.len()],
+            Color::WHITE,
+        );
+    }
+}
+
+/// Spawns the polygon owning its edge `Line`s, with a `Dot` on every vertex.
+#[hot]
+pub fn spawn_polygon(mut commands: Commands, polygon: Polygon) {
+    let positions = polygon_vertices(polygon.center, polygon.vertex, polygon.sides);
+
+    let entity = commands
+        .spawn((
+            polygon,
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+        ))
+        .id();
+    for (index, position) in positions.iter().enumerate() {
+        commands.spawn((
+            Dot {
+                position: *position,
+            },
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+        ));
+        commands.spawn((
+            Line {
+                start: *position,
+                end: positions[(index + 1) % positions.len()],
+            },
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+            ChildOf(entity),
+        ));
+    }
+}
+
+/// Vertices of a regular polygon around `center`, starting at `vertex` and going around the Y axis.
+pub fn polygon_vertices(center: Vec3, vertex: Vec3, sides: u32) -> Vec<Vec3> {
+    (0..sides)
+        .map(|index| {
+            center + Quat::from_rotation_y(TAU * index as f32 / sides as f32) * (vertex - center)
+        })
+        .collect()
+}
@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+
+use crate::reload::{ReloadLevel, Reloadable};
+
+use super::{dot::Dot, line::Line, size::MERGE_DISTANCE};
+
+#[derive(Component, Debug, Default)]
+pub struct Polyline {
+    pub points: Vec<Vec3>,
+    pub closed: bool,
+}
+
+impl Polyline {
+    /// Replaces the point at `corner` with `points`, turned to run from the previous point to the next.
+    /// Returns `false` when no point of the polyline lies at `corner`.
+    pub fn replace_corner(&mut self, corner: Vec3, mut points: Vec<Vec3>) -> bool {
+        let Some(index) = self
+            .points
+            .iter()
+            .position(|point| point.distance(corner) <= MERGE_DISTANCE)
+        else {
+            return false;
+        };
+        let count = self.points.len();
+        let (first, last) = (points[0], points[points.len() - 1]);
+        let reversed = if self.closed || index > 0 {
+            let previous = self.points[(index + count - 1) % count];
+            last.distance(previous) < first.distance(previous)
+        } else {
+            let next = self.points[(index + 1) % count];
+            first.distance(next) < last.distance(next)
+        };
+        if reversed {
+            points.reverse();
+        }
+        self.points.splice(index..=index, points);
+        true
+    }
+}
+
+/// Spawns a polyline owning one `Line` per segment, with a `Dot` on every point.
+pub fn spawn_polyline(commands: &mut Commands, points: Vec<Vec3>, closed: bool) -> Entity {
+    let segments = if closed {
+        points.len()
+    } else {
+        points.len().saturating_sub(1)
+    };
+    for position in points.iter() {
+        commands.spawn((
+            Dot {
+                position: *position,
+            },
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+        ));
+    }
+    let lines: Vec<_> = (0..segments)
+        .map(|index| {
+            (
+                Line {
+                    start: points[index],
+                    end: points[(index + 1) % points.len()],
+                },
+                Reloadable {
+                    level: ReloadLevel::Hard,
+                },
+            )
+        })
+        .collect();
+
+    commands
+        .spawn((
+            Polyline { points, closed },
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+        ))
+        .with_children(|parent| {
+            for line in lines {
+                parent.spawn(line);
+            }
+        })
+        .id()
+}
@@ -9,26 +9,54 @@ use crate::{
 use super::{
     dot::Dot,
     draw::{CurrentDrawing, DEFAULT_POS, DrawMode, reset_current_drawing},
+    geometry::plane_angle,
     line::Line,
 };
 
+/// Rectangle between opposite corners `start` and `end`.
+/// `rotation` turns its edges around +Y, so zero is axis-aligned.
 #[derive(Component, Debug)]
 pub struct Rectangle {
     pub start: Vec3,
     pub end: Vec3,
+    pub rotation: f32,
+}
+
+impl Rectangle {
+    /// Corners in drawing order, starting at `start` and running along the first edge.
+    pub fn corners(&self) -> [Vec3; 4] {
+        let rotation = Quat::from_rotation_y(self.rotation);
+        let along = rotation * Vec3::X;
+        let across = rotation * Vec3::Z;
+        let diagonal = self.end - self.start;
+        [
+            self.start,
+            self.start + along * diagonal.dot(along),
+            self.end,
+            self.start + across * diagonal.dot(across),
+        ]
+    }
 }
 
 pub struct RectanglePlugin;
 
 impl Plugin for RectanglePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, display_rectangles);
+        app.add_systems(
+            Update,
+            (
+                handle_draw_rectangle.run_if(in_state(DrawMode::Rectangle)),
+                handle_draw_center_rectangle.run_if(in_state(DrawMode::CenterRectangle)),
+                handle_draw_rotated_rectangle.run_if(in_state(DrawMode::RotatedRectangle)),
+            ),
+        )
+        .add_systems(Update, display_rectangles);
     }
 }
 
 #[hot]
 pub fn handle_draw_rectangle(
-    mut commands: Commands,
+    commands: Commands,
     mouse_input: Res<ButtonInput<MouseButton>>,
     cursor: Res<Cursor>,
     mut current_drawing: ResMut<CurrentDrawing>,
@@ -55,49 +83,87 @@ pub fn handle_draw_rectangle(
 
     // Create line and dots entities if both start and end are defined
     if start != DEFAULT_POS && end != DEFAULT_POS {
-        let positions = [
-            start,
-            vec3(end.x, 0., start.z),
-            end,
-            vec3(start.x, 0., end.z),
-        ];
-        let mut start_index = 0;
-        let mut end_index = positions.len() - 1;
-
-        commands.spawn((
+        spawn_rectangle(
+            commands,
             Rectangle {
-                start: start,
-                end: end,
-            },
-            Reloadable {
-                level: ReloadLevel::Hard,
+                start,
+                end,
+                rotation: 0.,
             },
-        ));
-        for position in positions {
-            commands.spawn((
-                Dot { position: position },
-                Reloadable {
-                    level: ReloadLevel::Hard,
-                },
-            ));
-
-            commands.spawn((
-                Line {
-                    start: positions[start_index],
-                    end: positions[end_index],
-                },
-                Reloadable {
-                    level: ReloadLevel::Hard,
-                },
-            ));
-
-            start_index += 1;
-            end_index = if end_index >= positions.len() - 1 {
-                0
-            } else {
-                end_index + 1
-            };
-        }
+        );
+        reset_current_drawing(current_drawing);
+    }
+}
+
+#[hot]
+pub fn handle_draw_center_rectangle(
+    commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+) {
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Define center of rectangle
+    if current_drawing.position[0] == DEFAULT_POS {
+        current_drawing.position[0] = cursor.position;
+    }
+    // Define corner
+    else if current_drawing.position[1] == DEFAULT_POS {
+        current_drawing.position[1] = cursor.position;
+    }
+
+    let center = current_drawing.position[0];
+    let corner = current_drawing.position[1];
+
+    // Create line and dots entities if both center and corner are defined
+    if center != DEFAULT_POS && corner != DEFAULT_POS {
+        spawn_rectangle(commands, center_rectangle(center, corner));
+        reset_current_drawing(current_drawing);
+    }
+}
+
+#[hot]
+pub fn handle_draw_rotated_rectangle(
+    commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+) {
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Define start of first edge
+    if current_drawing.position[0] == DEFAULT_POS {
+        current_drawing.position[0] = cursor.position;
+    }
+    // Define end of first edge, which fixes the orientation
+    else if current_drawing.position[1] == DEFAULT_POS {
+        current_drawing.position[1] = cursor.position;
+    }
+    // Define width
+    else if current_drawing.position[2] == DEFAULT_POS {
+        current_drawing.position[2] = cursor.position;
+    }
+
+    let start = current_drawing.position[0];
+    let edge = current_drawing.position[1];
+    let width = current_drawing.position[2];
+
+    // Create line and dots entities once the edge and width are defined
+    if start != DEFAULT_POS && edge != DEFAULT_POS && width != DEFAULT_POS {
+        spawn_rectangle(commands, rotated_rectangle(start, edge, width));
         reset_current_drawing(current_drawing);
     }
 }
@@ -109,25 +175,90 @@ pub fn display_rectangles(
     state: Res<State<DrawMode>>,
     current_drawing: ResMut<CurrentDrawing>,
 ) {
-    if state.get() == &DrawMode::Rectangle && current_drawing.position[0] != DEFAULT_POS {
-        let start = current_drawing.position[0];
-        let positions = [
-            start,
-            vec3(cursor.position.x, 0., start.z),
-            cursor.position,
-            vec3(start.x, 0., cursor.position.z),
-        ];
-        let mut start_index = 0;
-        let mut end_index = positions.len() - 1;
-
-        for _ in positions {
-            gizmos.line(positions[start_index], positions[end_index], Color::WHITE);
-            start_index += 1;
-            end_index = if end_index >= positions.len() - 1 {
-                0
-            } else {
-                end_index + 1
-            };
+    let first = current_drawing.position[0];
+    let second = current_drawing.position[1];
+    if first == DEFAULT_POS {
+        return;
+    }
+
+    let rectangle = match state.get() {
+        DrawMode::Rectangle => Rectangle {
+            start: first,
+            end: cursor.position,
+            rotation: 0.,
+        },
+        DrawMode::CenterRectangle => center_rectangle(first, cursor.position),
+        DrawMode::RotatedRectangle if second == DEFAULT_POS => {
+            gizmos.line(first, cursor.position, Color::WHITE);
+            return;
         }
+        DrawMode::RotatedRectangle => rotated_rectangle(first, second, cursor.position),
+        _ => {
+            return;
+        }
+    };
+
+    let positions = rectangle.corners();
+    for (index, position) in positions.iter().enumerate() {
+        gizmos.line(
+            *position,
+            positions[(index + 1) % positions.len()],
+            Color::WHITE,
+        );
+    }
+}
+
+/// Spawns the rectangle owning its four edge `Line`s, with a `Dot` on every corner.
+#[hot]
+pub fn spawn_rectangle(mut commands: Commands, rectangle: Rectangle) {
+    let positions = rectangle.corners();
+
+    let entity = commands
+        .spawn((
+            rectangle,
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+        ))
+        .id();
+    for (index, position) in positions.iter().enumerate() {
+        commands.spawn((
+            Dot {
+                position: *position,
+            },
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+        ));
+
+        commands.spawn((
+            Line {
+                start: *position,
+                end: positions[(index + 1) % positions.len()],
+            },
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+            ChildOf(entity),
+        ));
+    }
+}
+
+fn center_rectangle(center: Vec3, corner: Vec3) -> Rectangle {
+    Rectangle {
+        start: 2. * center - corner,
+        end: corner,
+        rotation: 0.,
+    }
+}
+
+/// The first edge runs from `start` to `edge`; `width` is projected across it.
+fn rotated_rectangle(start: Vec3, edge: Vec3, width: Vec3) -> Rectangle {
+    let rotation = plane_angle(edge - start);
+    let across = Quat::from_rotation_y(rotation) * Vec3::Z;
+    Rectangle {
+        start,
+        end: edge + across * (width - start).dot(across),
+        rotation,
     }
 }
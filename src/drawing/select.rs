@@ -0,0 +1,468 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_simple_subsecond_system::*;
+
+use crate::{
+    cursor::Cursor,
+    reload::{ReloadLevel, Reloadable},
+};
+
+use super::{
+    arc::Arc,
+    circle::Circle,
+    dot::Dot,
+    draw::{DEFAULT_RESOLUTION, DrawMode},
+    geometry::plane_angle,
+    line::Line,
+    offset::{Segment, connect, spawn_chain},
+    pick::{pick_arc, pick_circle, pick_dot, pick_line},
+    polygon::{Polygon, spawn_polygon},
+    polyline::Polyline,
+    rectangle::{Rectangle, spawn_rectangle},
+    size::{DOT_RADIUS, MERGE_DISTANCE},
+    slot::{Slot, spawn_slot},
+    text::{TextEditing, edit_text},
+};
+
+pub const SELECTED_COLOR: Color = Color::srgb(1., 0.6, 0.);
+
+/// Shape picked for the tools that act on a selection.
+/// Picking a line or arc of a rectangle, polygon, polyline or slot selects the whole outline.
+#[derive(Component, Debug, Default)]
+pub struct Selected;
+
+type IsOutline = Or<(With<Rectangle>, With<Polygon>, With<Polyline>, With<Slot>)>;
+
+/// Shapes that can be picked into the selection.
+#[derive(SystemParam)]
+pub struct SelectableShapes<'w, 's> {
+    dots: Query<'w, 's, (Entity, &'static Dot)>,
+    lines: Query<'w, 's, (Entity, &'static Line, Option<&'static ChildOf>)>,
+    arcs: Query<'w, 's, (Entity, &'static Arc, Option<&'static ChildOf>)>,
+    circles: Query<'w, 's, (Entity, &'static Circle)>,
+    outlines: Query<'w, 's, (), IsOutline>,
+}
+
+impl SelectableShapes<'_, '_> {
+    pub fn pick(&self, point: Vec3) -> Option<Entity> {
+        pick_dot(self.dots.iter(), point)
+            .map(|(entity, _)| entity)
+            .or_else(|| self.pick_shape(point))
+    }
+
+    /// Like `pick`, but passing over `Dot`s to the shapes they mark.
+    pub fn pick_shape(&self, point: Vec3) -> Option<Entity> {
+        let outline = |entity: Entity, owner: Option<&ChildOf>| {
+            owner
+                .map(|owner| owner.parent())
+                .filter(|owner| self.outlines.contains(*owner))
+                .unwrap_or(entity)
+        };
+        if let Some((entity, _)) = pick_line(
+            self.lines.iter().map(|(entity, line, _)| (entity, line)),
+            point,
+        ) {
+            Some(outline(entity, self.lines.get(entity).ok()?.2))
+        } else if let Some((entity, _)) = pick_arc(
+            self.arcs.iter().map(|(entity, arc, _)| (entity, arc)),
+            point,
+        ) {
+            Some(outline(entity, self.arcs.get(entity).ok()?.2))
+        } else {
+            pick_circle(self.circles.iter(), point).map(|(entity, _)| entity)
+        }
+    }
+}
+
+/// Selected shapes, with the pieces of selected outlines and the `Dot`s marking their points.
+#[derive(SystemParam)]
+pub struct SelectedShapes<'w, 's> {
+    commands: Commands<'w, 's>,
+    selected: Query<'w, 's, Entity, With<Selected>>,
+    dots: Query<'w, 's, &'static mut Dot, With<Selected>>,
+    lines: Query<'w, 's, &'static mut Line, With<Selected>>,
+    arcs: Query<'w, 's, &'static mut Arc, With<Selected>>,
+    circles: Query<'w, 's, &'static mut Circle, With<Selected>>,
+    rectangles: Query<'w, 's, (&'static mut Rectangle, &'static Children), With<Selected>>,
+    polygons: Query<'w, 's, (&'static mut Polygon, &'static Children), With<Selected>>,
+    polylines: Query<'w, 's, (&'static mut Polyline, &'static Children), With<Selected>>,
+    slots: Query<'w, 's, (&'static mut Slot, &'static Children), With<Selected>>,
+    unselected_lines: Query<'w, 's, (Entity, &'static mut Line), Without<Selected>>,
+    unselected_arcs: Query<'w, 's, (Entity, &'static mut Arc), Without<Selected>>,
+    unselected_circles: Query<'w, 's, &'static Circle, Without<Selected>>,
+    unselected_dots: Query<'w, 's, &'static mut Dot, Without<Selected>>,
+}
+
+impl SelectedShapes<'_, '_> {
+    pub fn is_empty(&self) -> bool {
+        self.selected.is_empty()
+    }
+
+    /// Spawns a copy of every selected shape moved by `map`, marked by `Dot`s like the original.
+    /// `mirrored` maps turn the plane over, which reverses arcs.
+    pub fn copy(&mut self, map: impl Fn(Vec3) -> Vec3, mirrored: bool) {
+        let reloadable = || Reloadable {
+            level: ReloadLevel::Hard,
+        };
+        for dot in self.dots.iter() {
+            self.commands.spawn((map_dot(dot, &map), reloadable()));
+        }
+        for line in self.lines.iter() {
+            self.commands.spawn((map_line(line, &map), reloadable()));
+        }
+        for arc in self.arcs.iter() {
+            self.commands
+                .spawn((map_arc(arc, &map, mirrored), reloadable()));
+        }
+        for circle in self.circles.iter() {
+            self.commands
+                .spawn((map_circle(circle, &map), reloadable()));
+        }
+        for (rectangle, _) in self.rectangles.iter() {
+            spawn_rectangle(self.commands.reborrow(), map_rectangle(rectangle, &map));
+        }
+        for (polygon, _) in self.polygons.iter() {
+            spawn_polygon(self.commands.reborrow(), map_polygon(polygon, &map));
+        }
+        for (slot, _) in self.slots.iter() {
+            spawn_slot(self.commands.reborrow(), map_slot(slot, &map));
+        }
+        for (_, children) in self.polylines.iter() {
+            let segments = children
+                .iter()
+                .filter_map(|child| {
+                    if let Ok((_, line)) = self.unselected_lines.get(child) {
+                        Some(Segment::Line(map_line(line, &map)))
+                    } else {
+                        self.unselected_arcs
+                            .get(child)
+                            .ok()
+                            .map(|(_, arc)| Segment::Arc(map_arc(arc, &map, mirrored)))
+                    }
+                })
+                .collect();
+            let (segments, closed) = connect(segments);
+            spawn_chain(&mut self.commands, segments, closed);
+        }
+
+        // Outlines spawn their own Dots, so only loose shapes need theirs copied
+        let mut marked: Vec<Vec3> = Vec::new();
+        let points = self
+            .lines
+            .iter()
+            .flat_map(line_points)
+            .chain(self.arcs.iter().flat_map(arc_points))
+            .chain(self.circles.iter().map(|circle| circle.center));
+        for point in points {
+            if !is_on(&marked, point)
+                && self
+                    .unselected_dots
+                    .iter()
+                    .any(|dot| dot.position.distance(point) <= MERGE_DISTANCE)
+            {
+                marked.push(point);
+            }
+        }
+        for point in marked {
+            self.commands.spawn((
+                Dot {
+                    position: map(point),
+                },
+                reloadable(),
+            ));
+        }
+    }
+
+    /// Moves every selected shape by `map`, along with the pieces of outlines and the `Dot`s
+    /// marking their points. A `Dot` shared with a shape that stays put stays with it, and the
+    /// moved shape gets a new one.
+    pub fn apply(&mut self, map: impl Fn(Vec3) -> Vec3, mirrored: bool) {
+        for mut dot in self.dots.iter_mut() {
+            *dot = map_dot(&dot, &map);
+        }
+        let mut points = Vec::new();
+        for mut line in self.lines.iter_mut() {
+            points.extend(line_points(&line));
+            *line = map_line(&line, &map);
+        }
+        for mut arc in self.arcs.iter_mut() {
+            points.extend(arc_points(&arc));
+            *arc = map_arc(&arc, &map, mirrored);
+        }
+        for mut circle in self.circles.iter_mut() {
+            points.push(circle.center);
+            *circle = map_circle(&circle, &map);
+        }
+
+        // Outlines carry their pieces along
+        let mut pieces = Vec::new();
+        for (mut rectangle, children) in self.rectangles.iter_mut() {
+            pieces.extend(children.iter());
+            *rectangle = map_rectangle(&rectangle, &map);
+        }
+        for (mut polygon, children) in self.polygons.iter_mut() {
+            pieces.extend(children.iter());
+            *polygon = map_polygon(&polygon, &map);
+        }
+        for (mut polyline, children) in self.polylines.iter_mut() {
+            pieces.extend(children.iter());
+            polyline.points = polyline.points.iter().map(|point| map(*point)).collect();
+        }
+        for (mut slot, children) in self.slots.iter_mut() {
+            pieces.extend(children.iter());
+            *slot = map_slot(&slot, &map);
+        }
+        for piece in pieces.iter() {
+            if let Ok((_, mut line)) = self.unselected_lines.get_mut(*piece) {
+                points.extend(line_points(&line));
+                *line = map_line(&line, &map);
+            } else if let Ok((_, mut arc)) = self.unselected_arcs.get_mut(*piece) {
+                points.extend(arc_points(&arc));
+                *arc = map_arc(&arc, &map, mirrored);
+            }
+        }
+
+        // Points still marking shapes that stay put
+        let kept: Vec<Vec3> = self
+            .unselected_lines
+            .iter()
+            .filter(|(entity, _)| !pieces.contains(entity))
+            .flat_map(|(_, line)| line_points(line))
+            .chain(
+                self.unselected_arcs
+                    .iter()
+                    .filter(|(entity, _)| !pieces.contains(entity))
+                    .flat_map(|(_, arc)| arc_points(arc)),
+            )
+            .chain(self.unselected_circles.iter().map(|circle| circle.center))
+            .collect();
+        for mut dot in self.unselected_dots.iter_mut() {
+            if !is_on(&points, dot.position) {
+                continue;
+            }
+            if is_on(&kept, dot.position) {
+                self.commands.spawn((
+                    map_dot(&dot, &map),
+                    Reloadable {
+                        level: ReloadLevel::Hard,
+                    },
+                ));
+            } else {
+                *dot = map_dot(&dot, &map);
+            }
+        }
+    }
+
+    /// Deselects everything.
+    pub fn clear(&mut self) {
+        for entity in self.selected.iter() {
+            self.commands.entity(entity).remove::<Selected>();
+        }
+    }
+}
+
+pub struct SelectPlugin;
+
+impl Plugin for SelectPlugin {
+    fn build(&self, app: &mut App) {
+        // Keys go to the edited annotation before Escape can deselect
+        app.add_systems(
+            Update,
+            (toggle_selection.before(edit_text), display_selection),
+        );
+    }
+}
+
+/// Modes whose tools act on the selection.
+pub fn selects(mode: &DrawMode) -> bool {
+    matches!(
+        mode,
+        DrawMode::Mirror | DrawMode::RectangularArray | DrawMode::PolarArray | DrawMode::PathArray
+    )
+}
+
+/// The selection, along with the commands that change it.
+#[derive(SystemParam)]
+struct Selection<'w, 's> {
+    commands: Commands<'w, 's>,
+    selected: Query<'w, 's, Entity, With<Selected>>,
+}
+
+impl Selection<'_, '_> {
+    fn clear(&mut self) {
+        for entity in self.selected.iter() {
+            self.commands.entity(entity).remove::<Selected>();
+        }
+    }
+
+    fn toggle(&mut self, entity: Entity) {
+        if self.selected.contains(entity) {
+            self.commands.entity(entity).remove::<Selected>();
+        } else {
+            self.commands.entity(entity).insert(Selected);
+        }
+    }
+}
+
+/// Shift-clicking a shape adds it to the selection or takes it out again. Escape deselects all.
+#[hot]
+fn toggle_selection(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    shapes: SelectableShapes,
+    mut selection: Selection,
+    text_editing: Res<TextEditing>,
+) {
+    if text_editing.entity.is_some() {
+        return;
+    }
+    if keyboard.just_pressed(KeyCode::Escape) {
+        selection.clear();
+        return;
+    }
+    if !selects(state.get())
+        || !keyboard.pressed(KeyCode::ShiftLeft)
+        || !mouse_input.just_pressed(MouseButton::Left)
+    {
+        return;
+    }
+
+    if let Some(entity) = shapes.pick(cursor.position) {
+        selection.toggle(entity);
+    }
+}
+
+/// Lines and arcs of the selected outlines, drawn along with them.
+#[derive(SystemParam)]
+struct SelectedOutlines<'w, 's> {
+    outlines: Query<'w, 's, &'static Children, With<Selected>>,
+    lines: Query<'w, 's, &'static Line>,
+    arcs: Query<'w, 's, &'static Arc>,
+}
+
+impl SelectedOutlines<'_, '_> {
+    fn pieces(&self) -> impl Iterator<Item = Entity> {
+        self.outlines.iter().flat_map(|children| children.iter())
+    }
+
+    fn lines(&self) -> impl Iterator<Item = &Line> {
+        self.pieces().filter_map(|piece| self.lines.get(piece).ok())
+    }
+
+    fn arcs(&self) -> impl Iterator<Item = &Arc> {
+        self.pieces().filter_map(|piece| self.arcs.get(piece).ok())
+    }
+}
+
+#[hot]
+fn display_selection(
+    mut gizmos: Gizmos,
+    dots: Query<&Dot, With<Selected>>,
+    lines: Query<&Line, With<Selected>>,
+    arcs: Query<&Arc, With<Selected>>,
+    circles: Query<&Circle, With<Selected>>,
+    outlines: SelectedOutlines,
+) {
+    // Redraw selected shapes slightly above the plane so they show over the regular ones
+    let lift = Vec3::Y * 1e-3;
+    let flat = Quat::from_rotation_arc(Vec3::Z, Vec3::Y);
+
+    for dot in dots.iter() {
+        gizmos.circle(
+            Isometry3d::new(dot.position + lift, flat),
+            DOT_RADIUS,
+            SELECTED_COLOR,
+        );
+    }
+    for line in lines.iter().chain(outlines.lines()) {
+        gizmos.line(line.start + lift, line.end + lift, SELECTED_COLOR);
+    }
+    for arc in arcs.iter().chain(outlines.arcs()) {
+        gizmos.linestrip(
+            arc.positions().into_iter().map(|position| position + lift),
+            SELECTED_COLOR,
+        );
+    }
+    for circle in circles.iter() {
+        gizmos
+            .circle(
+                Isometry3d::new(circle.center + lift, flat),
+                circle.radius,
+                SELECTED_COLOR,
+            )
+            .resolution(DEFAULT_RESOLUTION);
+    }
+}
+
+/// Points of a line a `Dot` can mark.
+fn line_points(line: &Line) -> [Vec3; 2] {
+    [line.start, line.end]
+}
+
+/// Points of an arc a `Dot` can mark.
+fn arc_points(arc: &Arc) -> [Vec3; 3] {
+    [arc.center, arc.start, arc.end]
+}
+
+/// Whether `position` is one of `points`.
+fn is_on(points: &[Vec3], position: Vec3) -> bool {
+    points
+        .iter()
+        .any(|point| point.distance(position) <= MERGE_DISTANCE)
+}
+
+fn map_dot(dot: &Dot, map: &impl Fn(Vec3) -> Vec3) -> Dot {
+    Dot {
+        position: map(dot.position),
+    }
+}
+
+fn map_line(line: &Line, map: &impl Fn(Vec3) -> Vec3) -> Line {
+    Line {
+        start: map(line.start),
+        end: map(line.end),
+    }
+}
+
+fn map_arc(arc: &Arc, map: &impl Fn(Vec3) -> Vec3, mirrored: bool) -> Arc {
+    Arc {
+        center: map(arc.center),
+        start: map(arc.start),
+        end: map(arc.end),
+        sweep: if mirrored { -arc.sweep } else { arc.sweep },
+    }
+}
+
+fn map_circle(circle: &Circle, map: &impl Fn(Vec3) -> Vec3) -> Circle {
+    Circle {
+        center: map(circle.center),
+        radius: circle.radius,
+    }
+}
+
+/// The rectangle's rotation follows wherever `map` sends its first edge.
+fn map_rectangle(rectangle: &Rectangle, map: &impl Fn(Vec3) -> Vec3) -> Rectangle {
+    let along = rectangle.start + Quat::from_rotation_y(rectangle.rotation) * Vec3::X;
+    Rectangle {
+        start: map(rectangle.start),
+        end: map(rectangle.end),
+        rotation: plane_angle(map(along) - map(rectangle.start)),
+    }
+}
+
+fn map_polygon(polygon: &Polygon, map: &impl Fn(Vec3) -> Vec3) -> Polygon {
+    Polygon {
+        center: map(polygon.center),
+        vertex: map(polygon.vertex),
+        sides: polygon.sides,
+    }
+}
+
+fn map_slot(slot: &Slot, map: &impl Fn(Vec3) -> Vec3) -> Slot {
+    Slot {
+        start: map(slot.start),
+        end: map(slot.end),
+        width: slot.width,
+    }
+}
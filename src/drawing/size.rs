@@ -1,2 +1,14 @@
 // pub const LINE_WIDTH: f32 = 10.;
 pub const DOT_RADIUS: f32 = 0.05;
+pub const CLOSE_RADIUS: f32 = 0.1;
+pub const FREEHAND_TOLERANCE: f32 = 0.02;
+pub const TEXT_HEIGHT: f32 = 0.2;
+pub const PICK_RADIUS: f32 = 0.1;
+pub const ARROW_SIZE: f32 = 0.1;
+pub const HATCH_SPACING: f32 = 0.1;
+pub const MERGE_DISTANCE: f32 = 1e-4;
+pub const FILLET_RADIUS: f32 = 0.2;
+pub const CHAMFER_DISTANCE: f32 = 0.2;
+pub const DASH_LENGTH: f32 = 0.05;
+pub const XLINE_EXTENT: f32 = 50.;
+pub const MEASURE_DISTANCE: f32 = 0.25;
@@ -0,0 +1,168 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_simple_subsecond_system::*;
+
+use crate::{
+    cursor::Cursor,
+    reload::{ReloadLevel, Reloadable},
+};
+
+use super::{
+    arc::Arc,
+    dot::Dot,
+    draw::{CurrentDrawing, DEFAULT_POS, DrawMode, reset_current_drawing},
+    line::Line,
+    offset::Segment,
+};
+
+/// Obround around the axis from `start` to `end`: two half circles of diameter `width`
+/// on the ends, joined by straight sides.
+#[derive(Component, Debug)]
+pub struct Slot {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub width: f32,
+}
+
+impl Slot {
+    /// Sides and ends in drawing order, starting with the side left of the axis.
+    pub fn segments(&self) -> [Segment; 4] {
+        let side = Vec3::Y.cross(self.end - self.start).normalize_or_zero() * self.width / 2.;
+        [
+            Segment::Line(Line {
+                start: self.start + side,
+                end: self.end + side,
+            }),
+            Segment::Arc(Arc::new(self.end, self.end + side, -PI)),
+            Segment::Line(Line {
+                start: self.end - side,
+                end: self.start - side,
+            }),
+            Segment::Arc(Arc::new(self.start, self.start - side, -PI)),
+        ]
+    }
+}
+
+pub struct SlotPlugin;
+
+impl Plugin for SlotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, handle_draw_slot.run_if(in_state(DrawMode::Slot)))
+            .add_systems(Update, display_slot_preview);
+    }
+}
+
+#[hot]
+fn handle_draw_slot(
+    commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+) {
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Define first center
+    if current_drawing.position[0] == DEFAULT_POS {
+        current_drawing.position[0] = cursor.position;
+    }
+    // Define second center
+    else if current_drawing.position[1] == DEFAULT_POS {
+        current_drawing.position[1] = cursor.position;
+    }
+    // Define width
+    else if current_drawing.position[2] == DEFAULT_POS {
+        current_drawing.position[2] = cursor.position;
+    }
+
+    let start = current_drawing.position[0];
+    let end = current_drawing.position[1];
+    let width = current_drawing.position[2];
+
+    // Create the slot once both centers and the width are defined
+    if start != DEFAULT_POS && end != DEFAULT_POS && width != DEFAULT_POS {
+        let slot = slot(start, end, width);
+        if start != end && slot.width > 0. {
+            spawn_slot(commands, slot);
+        }
+        reset_current_drawing(current_drawing);
+    }
+}
+
+#[hot]
+fn display_slot_preview(
+    mut gizmos: Gizmos,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    current_drawing: Res<CurrentDrawing>,
+) {
+    let start = current_drawing.position[0];
+    let end = current_drawing.position[1];
+    if state.get() != &DrawMode::Slot || start == DEFAULT_POS {
+        return;
+    }
+
+    // Display axis until the second center is defined, then the slot
+    if end == DEFAULT_POS {
+        gizmos.line(start, cursor.position, Color::WHITE);
+        return;
+    }
+    for segment in slot(start, end, cursor.position).segments() {
+        match segment {
+            Segment::Line(line) => gizmos.line(line.start, line.end, Color::WHITE),
+            Segment::Arc(arc) => gizmos.linestrip(arc.positions(), Color::WHITE),
+        }
+    }
+}
+
+/// Spawns the slot owning its side `Line`s and end `Arc`s, with a `Dot` where they meet.
+#[hot]
+pub fn spawn_slot(mut commands: Commands, slot: Slot) {
+    let segments = slot.segments();
+
+    let entity = commands
+        .spawn((
+            slot,
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+        ))
+        .id();
+    for segment in segments {
+        commands.spawn((
+            Dot {
+                position: segment.start(),
+            },
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+        ));
+
+        let mut piece = match segment {
+            Segment::Line(line) => commands.spawn(line),
+            Segment::Arc(arc) => commands.spawn(arc),
+        };
+        piece.insert((
+            Reloadable {
+                level: ReloadLevel::Hard,
+            },
+            ChildOf(entity),
+        ));
+    }
+}
+
+/// The width is twice the distance from `width` to the axis.
+fn slot(start: Vec3, end: Vec3, width: Vec3) -> Slot {
+    let across = Vec3::Y.cross(end - start).normalize_or_zero();
+    Slot {
+        start,
+        end,
+        width: 2. * (width - start).dot(across).abs(),
+    }
+}
@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+use bevy_simple_subsecond_system::*;
+
+use crate::{
+    cursor::Cursor,
+    reload::{ReloadLevel, Reloadable},
+};
+
+use super::{
+    construction::Construction,
+    dot::Dot,
+    draw::{CurrentDrawing, DEFAULT_RESOLUTION, DrawMode, LineChain, reset_drawing},
+    size::DOT_RADIUS,
+};
+
+/// Catmull-Rom spline passing through every fit point.
+#[derive(Component, Debug, Default)]
+pub struct Spline {
+    pub points: Vec<Vec3>,
+}
+
+impl Spline {
+    /// Points along the curve through the fit points, as `spline_positions` samples them.
+    pub fn positions(&self) -> Vec<Vec3> {
+        spline_positions(&self.points)
+    }
+}
+
+pub struct SplinePlugin;
+
+impl Plugin for SplinePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            handle_draw_spline.run_if(in_state(DrawMode::Spline)),
+        )
+        .add_systems(Update, display_splines);
+    }
+}
+
+#[hot]
+pub fn handle_draw_spline(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    current_drawing: ResMut<CurrentDrawing>,
+    mut line_chain: ResMut<LineChain>,
+) {
+    // Commit the spline through the fit points placed so far
+    if mouse_input.just_pressed(MouseButton::Right) {
+        if line_chain.points.len() >= 2 {
+            for position in line_chain.points.iter() {
+                commands.spawn((
+                    Dot {
+                        position: *position,
+                    },
+                    Reloadable {
+                        level: ReloadLevel::Hard,
+                    },
+                ));
+            }
+            commands.spawn((
+                Spline {
+                    points: line_chain.points.clone(),
+                },
+                Reloadable {
+                    level: ReloadLevel::Hard,
+                },
+            ));
+        }
+        reset_drawing(current_drawing, line_chain);
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Define next fit point
+    line_chain.points.push(cursor.position);
+    line_chain.count += 1;
+}
+
+#[hot]
+fn display_splines(
+    mut gizmos: Gizmos,
+    query: Query<&Spline, Without<Construction>>,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    line_chain: Res<LineChain>,
+) {
+    // Display existing splines
+    for spline in query.iter() {
+        gizmos.linestrip(spline.positions(), Color::WHITE);
+    }
+    // Display currently drawn spline, running through the cursor
+    if state.get() != &DrawMode::Spline || line_chain.points.is_empty() {
+        return;
+    }
+
+    for position in line_chain.points.iter() {
+        gizmos.circle(
+            Isometry3d::new(
+                *position,
+                Quat::from_rotation_arc(Vec3::Z, Dir3::Y.as_vec3()),
+            ),
+            DOT_RADIUS,
+            Color::WHITE,
+        );
+    }
+    let mut points = line_chain.points.clone();
+    points.push(cursor.position);
+    gizmos.linestrip(spline_positions(&points), Color::WHITE);
+}
+
+/// Samples the Catmull-Rom curve through `points`, `DEFAULT_RESOLUTION` steps per span.
+pub fn spline_positions(points: &[Vec3]) -> Vec<Vec3> {
+    let Ok(curve) = CubicCardinalSpline::new_catmull_rom(points.to_vec()).to_curve() else {
+        return points.to_vec();
+    };
+    curve
+        .iter_positions(curve.segments().len() * DEFAULT_RESOLUTION as usize)
+        .collect()
+}
@@ -0,0 +1,244 @@
+use bevy::{
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+    ui::widget,
+};
+use bevy_simple_subsecond_system::*;
+
+use crate::{
+    cursor::Cursor,
+    reload::{ReloadLevel, Reloadable},
+};
+
+use super::{
+    dimension::Dimension,
+    draw::{CurrentDrawing, DEFAULT_POS, DrawMode, reset_current_drawing},
+    geometry::plane_angle,
+    size::TEXT_HEIGHT,
+};
+
+/// Text annotation lying on the Y=0 plane, with its baseline starting at `position`.
+/// `height` is in world units and `rotation` turns the baseline around +Y.
+/// It shows as a UI label laid over the plane, see `display_texts`.
+#[derive(Component, Debug, Default)]
+pub struct Text {
+    pub position: Vec3,
+    pub height: f32,
+    pub rotation: f32,
+    pub content: String,
+}
+
+/// Annotation currently receiving keyboard input.
+#[derive(Resource, Default, Debug, PartialEq)]
+pub struct TextEditing {
+    pub entity: Option<Entity>,
+}
+
+pub struct TextPlugin;
+
+impl Plugin for TextPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TextEditing::default())
+            .add_systems(
+                Update,
+                (handle_draw_text.run_if(in_state(DrawMode::Text)), edit_text).chain(),
+            )
+            .add_systems(Update, (display_text_preview, display_texts));
+    }
+}
+
+#[hot]
+pub fn handle_draw_text(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+    mut text_editing: ResMut<TextEditing>,
+    query: Query<(Entity, &Text), Without<Dimension>>,
+) {
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Clicking an existing annotation edits it in place
+    if current_drawing.position[0] == DEFAULT_POS
+        && let Some((entity, _)) = query
+            .iter()
+            .find(|(_, text)| (cursor.position - text.position).length() <= text.height)
+    {
+        text_editing.entity = Some(entity);
+        return;
+    }
+
+    // Define position of text
+    if current_drawing.position[0] == DEFAULT_POS {
+        current_drawing.position[0] = cursor.position;
+    }
+    // Define direction of baseline
+    else if current_drawing.position[1] == DEFAULT_POS {
+        current_drawing.position[1] = cursor.position;
+    }
+
+    let position = current_drawing.position[0];
+    let direction = current_drawing.position[1];
+
+    // Create text entity and start editing it once position and direction are defined
+    if position != DEFAULT_POS && direction != DEFAULT_POS {
+        let entity = commands
+            .spawn((
+                text_label(Text {
+                    position,
+                    height: TEXT_HEIGHT,
+                    rotation: plane_angle(direction - position),
+                    content: String::new(),
+                }),
+                Reloadable {
+                    level: ReloadLevel::Hard,
+                },
+            ))
+            .id();
+        text_editing.entity = Some(entity);
+        reset_current_drawing(current_drawing);
+    }
+}
+
+/// Types into the edited annotation. One left empty when editing ends is removed.
+#[hot]
+pub fn edit_text(
+    mut commands: Commands,
+    mut keyboard_input: EventReader<KeyboardInput>,
+    mut text_editing: ResMut<TextEditing>,
+    mut query: Query<&mut Text>,
+) {
+    let Some(entity) = text_editing.entity else {
+        keyboard_input.clear();
+        return;
+    };
+    let Ok(mut text) = query.get_mut(entity) else {
+        text_editing.entity = None;
+        return;
+    };
+
+    for input in keyboard_input.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
+        match &input.logical_key {
+            Key::Enter | Key::Escape => {
+                if text.content.is_empty() {
+                    commands.entity(entity).despawn();
+                }
+                text_editing.entity = None;
+                return;
+            }
+            Key::Backspace => {
+                text.content.pop();
+            }
+            Key::Space => {
+                text.content.push(' ');
+            }
+            Key::Character(characters) => {
+                text.content.push_str(characters);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[hot]
+fn display_text_preview(
+    mut gizmos: Gizmos,
+    cursor: Res<Cursor>,
+    state: Res<State<DrawMode>>,
+    current_drawing: ResMut<CurrentDrawing>,
+) {
+    // Display baseline of currently placed text
+    if state.get() == &DrawMode::Text && current_drawing.position[0] != DEFAULT_POS {
+        gizmos.line(current_drawing.position[0], cursor.position, Color::WHITE);
+    }
+}
+
+/// Annotation together with the UI label `display_texts` lays over the drawing plane.
+pub fn text_label(text: Text) -> impl Bundle {
+    (
+        text,
+        widget::Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+    )
+}
+
+/// Lays each annotation's UI label over its spot on the drawing plane.
+///
+/// Bevy has no text that renders through a 3D camera, so labels stay screen-space UI nodes
+/// that follow the plane: they match its position, scale and turn, but draw over meshes
+/// instead of being hidden by them, and keep their shape when the view tilts the plane.
+#[hot]
+fn display_texts(
+    camera_query: Single<(&Camera, &GlobalTransform)>,
+    text_editing: Res<TextEditing>,
+    mut query: Query<(
+        Entity,
+        &Text,
+        &mut widget::Text,
+        &mut TextFont,
+        &mut Node,
+        &mut Transform,
+        &ComputedNode,
+    )>,
+) {
+    let (camera, camera_transform) = *camera_query;
+
+    for (entity, text, mut label, mut font, mut node, mut transform, computed) in query.iter_mut() {
+        let baseline = Quat::from_rotation_y(text.rotation) * Vec3::X;
+        let (Ok(start), Ok(along)) = (
+            camera.world_to_viewport(camera_transform, text.position),
+            camera.world_to_viewport(camera_transform, text.position + baseline),
+        ) else {
+            continue;
+        };
+        let along = along - start;
+
+        // Show a caret while the annotation is being edited
+        let content = if text_editing.entity == Some(entity) {
+            format!("{}|", text.content)
+        } else {
+            text.content.clone()
+        };
+        if label.0 != content {
+            label.0 = content;
+        }
+        // Only touch layout inputs when they move, since any change re-lays the text out
+        let font_size = text.height * along.length();
+        if font.font_size != font_size {
+            font.font_size = font_size;
+        }
+        // The label turns around its middle, so find where that lands with its bottom left
+        // corner on the start of the baseline
+        let size = computed.size() * computed.inverse_scale_factor();
+        let direction = along.normalize_or_zero();
+        let up = vec2(direction.y, -direction.x);
+        let center = start + direction * size.x / 2. + up * size.y / 2.;
+        let (left, top) = (
+            Val::Px(center.x - size.x / 2.),
+            Val::Px(center.y - size.y / 2.),
+        );
+        if node.left != left || node.top != top {
+            node.left = left;
+            node.top = top;
+        }
+        let rotation = Quat::from_rotation_z(f32::atan2(along.y, along.x));
+        if transform.rotation != rotation {
+            transform.rotation = rotation;
+        }
+    }
+}
@@ -0,0 +1,412 @@
+use std::f32::consts::TAU;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_simple_subsecond_system::*;
+
+use crate::{
+    cursor::Cursor,
+    reload::{ReloadLevel, Reloadable},
+};
+
+use super::{
+    arc::Arc,
+    circle::Circle,
+    dot::Dot,
+    draw::{CurrentDrawing, DEFAULT_POS, DrawMode, reset_current_drawing},
+    line::Line,
+    offset::Segment,
+    pick::{pick_arc, pick_circle, pick_line},
+    polygon::Polygon,
+    polyline::Polyline,
+    rectangle::Rectangle,
+    size::MERGE_DISTANCE,
+    slot::Slot,
+};
+
+/// Crossings closer than this to a segment's ends, as a fraction of it, count as its ends.
+const END_TOLERANCE: f32 = 1e-4;
+
+/// Shape under the cursor, with circles as a full turn starting on +X.
+#[derive(Debug, Clone, Copy)]
+enum Picked {
+    Segment(Entity, Segment),
+    Circle(Entity, Circle),
+}
+
+impl Picked {
+    fn entity(&self) -> Entity {
+        match self {
+            Picked::Segment(entity, _) | Picked::Circle(entity, _) => *entity,
+        }
+    }
+
+    fn segment(&self) -> Segment {
+        match self {
+            Picked::Segment(_, segment) => *segment,
+            Picked::Circle(_, circle) => Segment::Arc(Arc::new(
+                circle.center,
+                circle.center + Vec3::X * circle.radius,
+                TAU,
+            )),
+        }
+    }
+}
+
+/// Shapes that trim and extend cut against and edit.
+#[derive(SystemParam)]
+pub struct TrimShapes<'w, 's> {
+    commands: Commands<'w, 's>,
+    lines: Query<'w, 's, (Entity, &'static mut Line, Option<&'static ChildOf>)>,
+    arcs: Query<'w, 's, (Entity, &'static mut Arc, Option<&'static ChildOf>)>,
+    circles: Query<'w, 's, (Entity, &'static Circle)>,
+    dots: Query<'w, 's, (Entity, &'static mut Dot)>,
+}
+
+impl TrimShapes<'_, '_> {
+    fn pick(&self, point: Vec3) -> Option<Picked> {
+        if let Some((entity, line)) = pick_line(
+            self.lines.iter().map(|(entity, line, _)| (entity, line)),
+            point,
+        ) {
+            Some(Picked::Segment(entity, Segment::Line(*line)))
+        } else if let Some((entity, arc)) = pick_arc(
+            self.arcs.iter().map(|(entity, arc, _)| (entity, arc)),
+            point,
+        ) {
+            Some(Picked::Segment(entity, Segment::Arc(*arc)))
+        } else {
+            pick_circle(self.circles.iter(), point)
+                .map(|(entity, circle)| Picked::Circle(entity, *circle))
+        }
+    }
+
+    /// Every shape but `except`, as edges to cut against.
+    fn edges(&self, except: Entity) -> Vec<Segment> {
+        let lines = self
+            .lines
+            .iter()
+            .filter(|(entity, _, _)| *entity != except)
+            .map(|(_, line, _)| Segment::Line(*line));
+        let arcs = self
+            .arcs
+            .iter()
+            .filter(|(entity, _, _)| *entity != except)
+            .map(|(_, arc, _)| Segment::Arc(*arc));
+        let circles = self
+            .circles
+            .iter()
+            .filter(|(entity, _)| *entity != except)
+            .map(|(entity, circle)| Picked::Circle(entity, *circle).segment());
+        lines.chain(arcs).chain(circles).collect()
+    }
+
+    /// Swaps the shape `entity` for `pieces` of it, keeping `Dot`s on the ends of lines.
+    fn replace(&mut self, entity: Entity, original: Segment, pieces: Vec<Segment>) {
+        let near = |a: Vec3, b: Vec3| a.distance(b) <= MERGE_DISTANCE;
+        let is_line = matches!(original, Segment::Line(_));
+        for end in [original.start(), original.end()] {
+            if !pieces
+                .iter()
+                .any(|piece| near(piece.start(), end) || near(piece.end(), end))
+            {
+                self.release_end(entity, end, None);
+            }
+        }
+        for piece in pieces.iter().filter(|_| is_line) {
+            for end in [piece.start(), piece.end()] {
+                if !near(end, original.start()) && !near(end, original.end()) {
+                    self.commands.spawn((
+                        Dot { position: end },
+                        Reloadable {
+                            level: ReloadLevel::Hard,
+                        },
+                    ));
+                }
+            }
+        }
+
+        let owner = self.break_owner(entity);
+        let mut pieces = pieces.into_iter();
+        match pieces.next() {
+            Some(piece) => self.set(entity, piece),
+            None => self.commands.entity(entity).despawn(),
+        }
+        for piece in pieces {
+            let mut spawned = match piece {
+                Segment::Line(line) => self.commands.spawn(line),
+                Segment::Arc(arc) => self.commands.spawn(arc),
+            };
+            spawned.insert(Reloadable {
+                level: ReloadLevel::Hard,
+            });
+            if let Some(owner) = owner {
+                spawned.insert(ChildOf(owner));
+            }
+        }
+    }
+
+    fn set(&mut self, entity: Entity, segment: Segment) {
+        match segment {
+            Segment::Line(new) => {
+                if let Ok((_, mut line, _)) = self.lines.get_mut(entity) {
+                    *line = new;
+                }
+            }
+            Segment::Arc(new) => {
+                if let Ok((_, mut arc, _)) = self.arcs.get_mut(entity) {
+                    *arc = new;
+                }
+            }
+        }
+    }
+
+    /// Moves the `Dot` on an end of `entity` to `to`, or removes it when `to` is `None`.
+    /// Ends shared with other shapes keep their `Dot`, and a moved end gets a new one.
+    fn release_end(&mut self, entity: Entity, end: Vec3, to: Option<Vec3>) {
+        let near = |point: Vec3| point.distance(end) <= MERGE_DISTANCE;
+        let shared = self
+            .lines
+            .iter()
+            .any(|(other, line, _)| other != entity && (near(line.start) || near(line.end)))
+            || self
+                .arcs
+                .iter()
+                .any(|(other, arc, _)| other != entity && (near(arc.start) || near(arc.end)));
+        let dot = self.dots.iter_mut().find(|(_, dot)| near(dot.position));
+
+        match (shared, dot, to) {
+            (false, Some((_, mut dot)), Some(to)) => dot.position = to,
+            (false, Some((dot, _)), None) => self.commands.entity(dot).despawn(),
+            (true, Some(_), Some(to)) => {
+                self.commands.spawn((
+                    Dot { position: to },
+                    Reloadable {
+                        level: ReloadLevel::Hard,
+                    },
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    /// An edited shape no longer traces its polygon, polyline, rectangle or slot, so the owner stops
+    /// being one. The shapes stay grouped under it.
+    fn break_owner(&mut self, entity: Entity) -> Option<Entity> {
+        let owner = self
+            .lines
+            .get(entity)
+            .ok()
+            .and_then(|(_, _, owner)| owner)
+            .or_else(|| self.arcs.get(entity).ok().and_then(|(_, _, owner)| owner))
+            .map(|owner| owner.parent())?;
+        self.commands
+            .entity(owner)
+            .remove::<(Polygon, Polyline, Rectangle, Slot)>();
+        Some(owner)
+    }
+}
+
+pub struct TrimPlugin;
+
+impl Plugin for TrimPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, handle_draw_trim.run_if(in_state(DrawMode::Trim)))
+            .add_systems(
+                Update,
+                handle_draw_extend.run_if(in_state(DrawMode::Extend)),
+            );
+    }
+}
+
+#[hot]
+fn handle_draw_trim(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    mut shapes: TrimShapes,
+) {
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(picked) = shapes.pick(cursor.position) else {
+        return;
+    };
+    let segment = picked.segment();
+    let click = segment.parameter(cursor.position);
+    let crossings = crossings(&segment, &shapes.edges(picked.entity()));
+
+    match picked {
+        // Remove the piece between the crossings on either side of the click
+        Picked::Segment(entity, segment) => {
+            let inner: Vec<f32> = crossings
+                .into_iter()
+                .filter(|t| *t > END_TOLERANCE && *t < 1. - END_TOLERANCE)
+                .collect();
+            let before = inner
+                .iter()
+                .copied()
+                .filter(|t| *t < click)
+                .max_by(f32::total_cmp);
+            let after = inner
+                .iter()
+                .copied()
+                .filter(|t| *t > click)
+                .min_by(f32::total_cmp);
+            let pieces = match (before, after) {
+                (None, None) => Vec::new(),
+                (Some(before), None) => vec![segment.between(0., before)],
+                (None, Some(after)) => vec![segment.between(after, 1.)],
+                (Some(before), Some(after)) => {
+                    vec![segment.between(0., before), segment.between(after, 1.)]
+                }
+            };
+            shapes.replace(entity, segment, pieces);
+        }
+        // A circle needs two crossings to open up, and becomes the arc left over
+        Picked::Circle(entity, circle) => {
+            let Some((from, sweep)) = circle_remainder(crossings, click) else {
+                warn!("A circle needs two crossings to trim");
+                return;
+            };
+            shapes.commands.spawn((
+                Arc::new(circle.center, segment.point_at(from), TAU * sweep),
+                Reloadable {
+                    level: ReloadLevel::Hard,
+                },
+            ));
+            shapes.commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[hot]
+fn handle_draw_extend(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    cursor: Res<Cursor>,
+    mut current_drawing: ResMut<CurrentDrawing>,
+    mut shapes: TrimShapes,
+) {
+    if mouse_input.just_pressed(MouseButton::Right) {
+        reset_current_drawing(current_drawing);
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Pick boundary
+    if current_drawing.position[0] == DEFAULT_POS {
+        if shapes.pick(cursor.position).is_some() {
+            current_drawing.position[0] = cursor.position;
+        }
+        return;
+    }
+    let Some(boundary) = shapes.pick(current_drawing.position[0]) else {
+        reset_current_drawing(current_drawing);
+        return;
+    };
+
+    // Pick line or arc to extend, which stays picked so several can reach the same boundary
+    let Some(Picked::Segment(entity, segment)) = shapes.pick(cursor.position) else {
+        return;
+    };
+    if entity == boundary.entity() {
+        return;
+    }
+
+    // Extend whichever end is closer to the click
+    let forward = segment.parameter(cursor.position) >= 0.5;
+    let oriented = if forward { segment } else { segment.reversed() };
+    let Some(reach) = crossings(&oriented, &[boundary.segment()])
+        .into_iter()
+        .filter(|t| *t > 1. + END_TOLERANCE)
+        .min_by(f32::total_cmp)
+    else {
+        warn!("{:?} does not reach the boundary", segment);
+        return;
+    };
+    let extended = oriented.between(0., reach);
+
+    if let Segment::Line(_) = segment {
+        shapes.release_end(entity, oriented.end(), Some(extended.end()));
+    }
+    shapes.break_owner(entity);
+    shapes.set(
+        entity,
+        if forward {
+            extended
+        } else {
+            extended.reversed()
+        },
+    );
+}
+
+/// Parameters along `segment` where its line or circle crosses any of `edges`.
+fn crossings(segment: &Segment, edges: &[Segment]) -> Vec<f32> {
+    edges
+        .iter()
+        .flat_map(|edge| {
+            segment
+                .intersections(edge)
+                .into_iter()
+                .filter(|point| on_edge(edge, *point))
+        })
+        .map(|point| segment.parameter(point))
+        .collect()
+}
+
+/// Start and size, as fractions of a turn, of what is left of a circle cut at `crossings`
+/// once the piece around `click` is removed. Needs two distinct crossings.
+fn circle_remainder(crossings: Vec<f32>, click: f32) -> Option<(f32, f32)> {
+    // A circle has no ends, so a crossing just short of a full turn lies on its start
+    let mut crossings: Vec<f32> = crossings
+        .into_iter()
+        .map(|t| if t > 1. - END_TOLERANCE { 0. } else { t })
+        .collect();
+    crossings.sort_by(f32::total_cmp);
+    crossings.dedup_by(|a, b| (*a - *b).abs() <= END_TOLERANCE);
+    if crossings.len() < 2 {
+        return None;
+    }
+
+    // Crossings on either side of the click, wrapping around the start
+    let before = crossings
+        .iter()
+        .copied()
+        .filter(|t| *t < click)
+        .max_by(f32::total_cmp)
+        .or(crossings.last().copied())?;
+    let after = crossings
+        .iter()
+        .copied()
+        .filter(|t| *t > click)
+        .min_by(f32::total_cmp)
+        .or(crossings.first().copied())?;
+    Some((after, (before - after).rem_euclid(1.)))
+}
+
+fn on_edge(edge: &Segment, point: Vec3) -> bool {
+    match edge {
+        Segment::Line(_) => (-END_TOLERANCE..=1. + END_TOLERANCE).contains(&edge.parameter(point)),
+        Segment::Arc(arc) => arc.contains_angle(point),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circle_crossing_at_wraparound() {
+        // A line through the center crosses on the start, which can come out a full turn later
+        let remainder = circle_remainder(vec![1. - 1e-6, 0.5], 0.25);
+        assert!(remainder.is_some_and(|(from, sweep)| {
+            (from - 0.5).abs() < 1e-5 && (sweep - 0.5).abs() < 1e-5
+        }));
+    }
+
+    #[test]
+    fn circle_needs_two_crossings() {
+        assert_eq!(circle_remainder(vec![0.3], 0.5), None);
+        assert_eq!(circle_remainder(vec![0., 1. - 1e-6], 0.5), None);
+    }
+}
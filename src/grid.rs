@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+use bevy_simple_subsecond_system::hot;
+
+pub const GRID_SPACING: f32 = 0.1;
+/// Halving and doubling the spacing stops at these.
+pub const MIN_GRID_SPACING: f32 = GRID_SPACING / 64.;
+pub const MAX_GRID_SPACING: f32 = GRID_SPACING * 64.;
+/// Every this many minor lines is a major one.
+pub const MAJOR_EVERY: u32 = 5;
+/// Zooming out until more lines than this would cross the view coarsens the grid.
+pub const MAX_GRID_LINES: f32 = 100.;
+
+const MINOR_COLOR: Color = Color::srgba(1., 1., 1., 0.06);
+const MAJOR_COLOR: Color = Color::srgba(1., 1., 1., 0.15);
+
+/// Grid on the drawing plane, and whether clicks snap to its `spacing`.
+#[derive(Resource, Debug, PartialEq)]
+pub struct Grid {
+    pub spacing: f32,
+    pub visible: bool,
+    pub snap: bool,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Grid {
+            spacing: GRID_SPACING,
+            visible: true,
+            snap: false,
+        }
+    }
+}
+
+impl Grid {
+    /// Closest grid point to `point` on the plane.
+    pub fn snapped(&self, point: Vec3) -> Vec3 {
+        let round = |value: f32| (value / self.spacing).round() * self.spacing;
+        vec3(round(point.x), point.y, round(point.z))
+    }
+}
+
+pub struct GridPlugin;
+
+impl Plugin for GridPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Grid::default())
+            .add_systems(Update, (change_grid, display_grid));
+    }
+}
+
+/// F7 shows or hides the grid, F9 toggles snapping and Page Up/Down double or halve the spacing.
+#[hot]
+fn change_grid(keyboard: Res<ButtonInput<KeyCode>>, mut grid: ResMut<Grid>) {
+    if keyboard.just_pressed(KeyCode::F7) {
+        grid.visible = !grid.visible;
+    } else if keyboard.just_pressed(KeyCode::F9) {
+        grid.snap = !grid.snap;
+    } else if keyboard.just_pressed(KeyCode::PageUp) {
+        grid.spacing = (grid.spacing * 2.).min(MAX_GRID_SPACING);
+    } else if keyboard.just_pressed(KeyCode::PageDown) {
+        grid.spacing = (grid.spacing / 2.).max(MIN_GRID_SPACING);
+    } else {
+        return;
+    }
+    info!("Grid: {:?}", *grid);
+}
+
+#[hot]
+fn display_grid(
+    mut gizmos: Gizmos,
+    grid: Res<Grid>,
+    camera_query: Single<(&Camera, &GlobalTransform)>,
+) {
+    if !grid.visible {
+        return;
+    }
+
+    // Find the part of the plane in view from where the viewport's corners land on it
+    let (camera, camera_transform) = *camera_query;
+    let Some(size) = camera.logical_viewport_size() else {
+        return;
+    };
+    let corners: Vec<Vec3> = [Vec2::ZERO, size]
+        .into_iter()
+        .filter_map(|corner| {
+            let ray = camera.viewport_to_world(camera_transform, corner).ok()?;
+            let distance = ray.intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Dir3::Y))?;
+            Some(ray.get_point(distance))
+        })
+        .collect();
+    let [first, second] = corners[..] else {
+        return;
+    };
+    let min = first.min(second);
+    let max = first.max(second);
+
+    // Zoomed out, the major lines become the minor ones
+    let extent = (max.x - min.x).max(max.z - min.z);
+    let mut spacing = grid.spacing;
+    // Coarsening could never catch up with an endless view or a spacing of nothing
+    if !extent.is_finite() || spacing <= 0. {
+        return;
+    }
+    while extent / spacing > MAX_GRID_LINES {
+        spacing *= MAJOR_EVERY as f32;
+    }
+
+    // Keep the grid just under the plane so shapes draw over it
+    let below = -1e-3;
+    let color = |index: i32| {
+        if index.rem_euclid(MAJOR_EVERY as i32) == 0 {
+            MAJOR_COLOR
+        } else {
+            MINOR_COLOR
+        }
+    };
+    for index in (min.x / spacing).ceil() as i32..=(max.x / spacing).floor() as i32 {
+        let x = index as f32 * spacing;
+        gizmos.line(vec3(x, below, min.z), vec3(x, below, max.z), color(index));
+    }
+    for index in (min.z / spacing).ceil() as i32..=(max.z / spacing).floor() as i32 {
+        let z = index as f32 * spacing;
+        gizmos.line(vec3(min.x, below, z), vec3(max.x, below, z), color(index));
+    }
+}
@@ -1,14 +1,18 @@
 mod cursor;
 mod drawing;
+mod grid;
 mod mesh;
 mod reload;
+mod snap;
 
 use bevy::{prelude::*, render::camera::ScalingMode};
 use bevy_simple_subsecond_system::*;
 use cursor::CursorPlugin;
 use drawing::draw::DrawPlugin;
+use grid::GridPlugin;
 use mesh::mesh::MeshPlugin;
 use reload::{ReloadPlugin, Reloadable};
+use snap::SnapPlugin;
 
 fn main() {
     App::new()
@@ -16,6 +20,8 @@ fn main() {
         .add_plugins(SimpleSubsecondPlugin::default())
         .add_plugins(CursorPlugin)
         .add_plugins(DrawPlugin)
+        .add_plugins(GridPlugin)
+        .add_plugins(SnapPlugin)
         .add_plugins(MeshPlugin)
         .add_plugins(ReloadPlugin)
         .add_systems(Startup, setup)
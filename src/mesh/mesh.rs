@@ -1,7 +1,24 @@
-use bevy::{asset::RenderAssetUsages, prelude::*};
+use bevy::{asset::RenderAssetUsages, ecs::system::SystemParam, prelude::*};
 use bevy_simple_subsecond_system::hot;
 
-use crate::drawing::rectangle::Rectangle;
+use crate::drawing::{
+    construction::Construction,
+    polygon::{Polygon, polygon_vertices},
+    polyline::Polyline,
+    rectangle::Rectangle,
+    text::TextEditing,
+};
+
+use super::triangulate::triangulate;
+
+/// Shapes that get meshed, leaving out construction geometry.
+/// An arc only bounds an area as part of a closed polyline, so it gets meshed with that.
+#[derive(SystemParam)]
+pub struct MeshShapes<'w, 's> {
+    rectangles: Query<'w, 's, &'static Rectangle, Without<Construction>>,
+    polygons: Query<'w, 's, &'static Polygon, Without<Construction>>,
+    polylines: Query<'w, 's, &'static Polyline, Without<Construction>>,
+}
 
 pub struct MeshPlugin;
 
@@ -17,13 +34,17 @@ pub fn handle_create_mesh(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    query: Query<&Rectangle>,
+    shapes: MeshShapes,
+    text_editing: Res<TextEditing>,
 ) {
-    if !keyboard.just_pressed(KeyCode::Space) {
+    // Space types into the edited annotation instead
+    if !keyboard.just_pressed(KeyCode::Space) || text_editing.entity.is_some() {
         return;
     }
 
-    let generated_meshes = create_mesh_from_rectangles(query);
+    let mut generated_meshes = create_mesh_from_rectangles(shapes.rectangles);
+    generated_meshes.extend(create_mesh_from_polygons(shapes.polygons));
+    generated_meshes.extend(create_mesh_from_polylines(shapes.polylines));
     for mesh in generated_meshes {
         commands.spawn((
             Mesh3d(meshes.add(mesh)),
@@ -36,20 +57,12 @@ pub fn handle_create_mesh(
 }
 
 #[hot]
-fn create_mesh_from_rectangles(query: Query<&Rectangle>) -> Vec<Mesh> {
+fn create_mesh_from_rectangles(query: Query<&Rectangle, Without<Construction>>) -> Vec<Mesh> {
     let mut meshes: Vec<Mesh> = Vec::new();
     let mut indices = Vec::new();
 
     for rect in query.iter() {
-        let start = rect.start;
-        let end = rect.end;
-        println!("{:?}, {:?}", start, end);
-        let positions = [
-            start,
-            vec3(end.x, 0., start.z),
-            end,
-            vec3(start.x, 0., end.z),
-        ];
+        let positions = rect.corners();
         let mut vertices: Vec<Vec3> = Vec::new();
         let normals = vec![[0f32, 0f32, 1f32]; 4];
 
@@ -59,7 +72,7 @@ fn create_mesh_from_rectangles(query: Query<&Rectangle>) -> Vec<Mesh> {
         for i in 2..positions.len() as u32 {
             indices.extend_from_slice(&[i - 1, i, 0]);
         }
-        println!("{:?}", indices);
+        debug!("{:?}", indices);
         meshes.push(
             Mesh::new(
                 bevy::render::mesh::PrimitiveTopology::TriangleList,
@@ -71,5 +84,58 @@ fn create_mesh_from_rectangles(query: Query<&Rectangle>) -> Vec<Mesh> {
         );
         indices.clear()
     }
-    return meshes;
+    meshes
+}
+
+#[hot]
+fn create_mesh_from_polygons(query: Query<&Polygon, Without<Construction>>) -> Vec<Mesh> {
+    let mut meshes: Vec<Mesh> = Vec::new();
+
+    for polygon in query.iter() {
+        // Fan out from the center, which is vertex 0
+        let mut vertices = vec![polygon.center];
+        vertices.extend(polygon_vertices(
+            polygon.center,
+            polygon.vertex,
+            polygon.sides,
+        ));
+        let normals = vec![[0f32, 1f32, 0f32]; vertices.len()];
+        let mut indices = Vec::new();
+        for i in 1..=polygon.sides {
+            indices.extend_from_slice(&[0, i, i % polygon.sides + 1]);
+        }
+        meshes.push(
+            Mesh::new(
+                bevy::render::mesh::PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            )
+            .with_inserted_indices(bevy::render::mesh::Indices::U32(indices))
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vertices)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals),
+        );
+    }
+    meshes
+}
+
+#[hot]
+fn create_mesh_from_polylines(query: Query<&Polyline, Without<Construction>>) -> Vec<Mesh> {
+    let mut meshes: Vec<Mesh> = Vec::new();
+
+    for polyline in query.iter().filter(|polyline| polyline.closed) {
+        let indices = triangulate(&polyline.points);
+        if indices.is_empty() {
+            continue;
+        }
+        let normals = vec![[0f32, 1f32, 0f32]; polyline.points.len()];
+        meshes.push(
+            Mesh::new(
+                bevy::render::mesh::PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            )
+            .with_inserted_indices(bevy::render::mesh::Indices::U32(indices))
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, polyline.points.clone())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals),
+        );
+    }
+    meshes
 }
@@ -1 +1,3 @@
+#[allow(clippy::module_inception)]
 pub mod mesh;
+pub mod triangulate;
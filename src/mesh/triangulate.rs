@@ -0,0 +1,199 @@
+use bevy::prelude::*;
+
+/// Triangulates a simple polygon on the Y=0 plane by ear clipping.
+/// Returns indices into `points`, wound so that every triangle faces +Y.
+pub fn triangulate(points: &[Vec3]) -> Vec<u32> {
+    let mut indices = Vec::new();
+    if points.len() < 3 {
+        return indices;
+    }
+
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    // Ears are clipped clockwise in XZ, which is counter-clockwise seen from +Y
+    if signed_area(points) > 0. {
+        remaining.reverse();
+    }
+
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let ear = (0..count).find(|&i| {
+            let a = points[remaining[(i + count - 1) % count]];
+            let b = points[remaining[i]];
+            let c = points[remaining[(i + 1) % count]];
+            cross(a, b, c) < 0.
+                && remaining
+                    .iter()
+                    .map(|&index| points[index])
+                    .filter(|&p| p != a && p != b && p != c)
+                    .all(|p| !in_triangle(p, a, b, c))
+        });
+        // Self-intersecting or degenerate outlines have no ear left to clip
+        let Some(i) = ear else {
+            break;
+        };
+        indices.extend_from_slice(&[
+            remaining[(i + count - 1) % count] as u32,
+            remaining[i] as u32,
+            remaining[(i + 1) % count] as u32,
+        ]);
+        remaining.remove(i);
+    }
+    if remaining.len() == 3 {
+        indices.extend(remaining.iter().map(|&index| index as u32));
+    }
+    indices
+}
+
+/// Triangulates the area inside `outline` and outside every one of `holes`.
+/// Each hole is cut open into the outline along a bridge, so the result is one outline
+/// through both, whose points the returned indices refer to.
+pub fn triangulate_with_holes(outline: &[Vec3], holes: &[Vec<Vec3>]) -> (Vec<Vec3>, Vec<u32>) {
+    let mut points = outline.to_vec();
+    if signed_area(&points) < 0. {
+        points.reverse();
+    }
+    let mut holes: Vec<Vec<Vec3>> = holes
+        .iter()
+        .filter(|hole| hole.len() >= 3)
+        .map(|hole| {
+            // Holes run against the outline, so the bridge walks around them the other way
+            let mut hole = hole.clone();
+            if signed_area(&hole) > 0. {
+                hole.reverse();
+            }
+            hole
+        })
+        .collect();
+    // Bridging from the rightmost hole first keeps later bridges clear of earlier ones
+    let rightmost = |hole: &[Vec3]| hole.iter().map(|point| point.x).fold(f32::MIN, f32::max);
+    holes.sort_by(|a, b| rightmost(b).total_cmp(&rightmost(a)));
+
+    for (index, hole) in holes.iter().enumerate() {
+        let start = (0..hole.len())
+            .max_by(|&a, &b| hole[a].x.total_cmp(&hole[b].x))
+            .unwrap_or(0);
+        let from = hole[start];
+        // Nearest outline point the bridge reaches without crossing any edge
+        let edges = points
+            .iter()
+            .zip(points.iter().cycle().skip(1))
+            .chain(
+                holes[index..]
+                    .iter()
+                    .flat_map(|hole| hole.iter().zip(hole.iter().cycle().skip(1))),
+            )
+            .map(|(a, b)| (*a, *b))
+            .collect::<Vec<_>>();
+        let Some(to) = (0..points.len())
+            .filter(|&candidate| {
+                let to = points[candidate];
+                edges.iter().all(|&(a, b)| {
+                    a == to || b == to || a == from || b == from || !crosses(from, to, a, b)
+                })
+            })
+            .min_by(|&a, &b| {
+                points[a]
+                    .distance(from)
+                    .total_cmp(&points[b].distance(from))
+            })
+        else {
+            continue;
+        };
+
+        // Into the hole, once around it, and back out along the same bridge
+        let mut bridged = points[..=to].to_vec();
+        bridged.extend(hole[start..].iter().chain(hole[..=start].iter()));
+        bridged.extend_from_slice(&points[to..]);
+        points = bridged;
+    }
+    let indices = triangulate(&points);
+    (points, indices)
+}
+
+/// Whether the segments from `a` to `b` and from `c` to `d` cross.
+fn crosses(a: Vec3, b: Vec3, c: Vec3, d: Vec3) -> bool {
+    (cross(a, b, c) > 0.) != (cross(a, b, d) > 0.) && (cross(c, d, a) > 0.) != (cross(c, d, b) > 0.)
+}
+
+/// Twice the signed area of the outline, positive when counter-clockwise in XZ.
+fn signed_area(points: &[Vec3]) -> f32 {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a.x * b.z - b.x * a.z)
+        .sum()
+}
+
+fn cross(a: Vec3, b: Vec3, c: Vec3) -> f32 {
+    (b.x - a.x) * (c.z - a.z) - (b.z - a.z) * (c.x - a.x)
+}
+
+fn in_triangle(p: Vec3, a: Vec3, b: Vec3, c: Vec3) -> bool {
+    cross(a, b, p) <= 0. && cross(b, c, p) <= 0. && cross(c, a, p) <= 0.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks the triangles face +Y and cover the outline's whole area.
+    fn assert_covers(points: &[Vec3], indices: &[u32]) {
+        assert_eq!(indices.len(), (points.len() - 2) * 3);
+        let mut area = 0.;
+        for triangle in indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| points[triangle[i] as usize]);
+            let normal = (b - a).cross(c - a);
+            assert!(normal.y > 0., "triangle {triangle:?} faces down");
+            area += normal.y / 2.;
+        }
+        assert!((area - signed_area(points).abs() / 2.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn concave_outline() {
+        // L shape, with the reflex corner at (1, 1)
+        let points = [
+            vec3(0., 0., 0.),
+            vec3(2., 0., 0.),
+            vec3(2., 0., 1.),
+            vec3(1., 0., 1.),
+            vec3(1., 0., 2.),
+            vec3(0., 0., 2.),
+        ];
+        assert_covers(&points, &triangulate(&points));
+    }
+
+    #[test]
+    fn either_winding() {
+        let mut points = vec![
+            vec3(0., 0., 0.),
+            vec3(1., 0., 0.),
+            vec3(1., 0., 1.),
+            vec3(0., 0., 1.),
+        ];
+        assert_covers(&points, &triangulate(&points));
+        points.reverse();
+        assert_covers(&points, &triangulate(&points));
+    }
+
+    #[test]
+    fn square_with_a_hole() {
+        let outline = [
+            vec3(0., 0., 0.),
+            vec3(3., 0., 0.),
+            vec3(3., 0., 3.),
+            vec3(0., 0., 3.),
+        ];
+        let hole = vec![
+            vec3(1., 0., 1.),
+            vec3(2., 0., 1.),
+            vec3(2., 0., 2.),
+            vec3(1., 0., 2.),
+        ];
+        let (points, indices) = triangulate_with_holes(&outline, &[hole]);
+        assert_eq!(points.len(), 10);
+        // The bridge is walked both ways, so the area left is the outline's less the hole's
+        assert!((signed_area(&points).abs() / 2. - 8.).abs() < 1e-5);
+        assert_covers(&points, &indices);
+    }
+}
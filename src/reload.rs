@@ -1,10 +1,11 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
 use bevy_simple_subsecond_system::hot;
 
 use crate::{
     drawing::{
         self,
         draw::{CurrentDrawing, LineChain},
+        text::{TextEditing, edit_text},
     },
     setup,
 };
@@ -25,10 +26,18 @@ pub struct ReloadPlugin;
 
 impl Plugin for ReloadPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, handle_reload);
+        // Keys go to the edited annotation before they can reload
+        app.add_systems(Update, handle_reload.before(edit_text));
     }
 }
 
+/// Drawing in progress, dropped on reload.
+#[derive(SystemParam)]
+struct DrawingInProgress<'w> {
+    current_drawing: ResMut<'w, CurrentDrawing>,
+    line_chain: ResMut<'w, LineChain>,
+}
+
 #[hot]
 fn handle_reload(
     input: Res<ButtonInput<KeyCode>>,
@@ -36,9 +45,12 @@ fn handle_reload(
     meshes: ResMut<Assets<Mesh>>,
     materials: ResMut<Assets<StandardMaterial>>,
     query: Query<(Entity, &Reloadable)>,
-    current_drawing: ResMut<CurrentDrawing>,
-    line_chain: ResMut<LineChain>,
+    in_progress: DrawingInProgress,
+    text_editing: Res<TextEditing>,
 ) {
+    if text_editing.entity.is_some() {
+        return;
+    }
     if input.pressed(KeyCode::ControlLeft) && input.just_pressed(KeyCode::KeyR) {
         let reload_level = if input.pressed(KeyCode::ShiftLeft) {
             ReloadLevel::Hard
@@ -47,16 +59,17 @@ fn handle_reload(
         };
         for (entity, reloadable) in query.iter() {
             if reloadable.level <= reload_level {
-                commands.entity(entity).despawn();
+                // Children such as polyline segments may already be gone with their parent
+                commands.entity(entity).try_despawn();
             }
         }
-        drawing::draw::reset_drawing(current_drawing, line_chain);
+        drawing::draw::reset_drawing(in_progress.current_drawing, in_progress.line_chain);
         setup(commands, meshes, materials);
         let message = if reload_level == ReloadLevel::Soft {
             "Soft reloaded."
         } else {
             "Hard reloaded."
         };
-        println!("{:?}", message);
+        info!("{}", message);
     }
 }
@@ -0,0 +1,253 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_simple_subsecond_system::hot;
+
+use crate::{
+    cursor::Cursor,
+    drawing::{arc::Arc, circle::Circle, dot::Dot, line::Line, rectangle::Rectangle},
+};
+
+/// Screen distance in pixels within which the cursor snaps onto a point.
+pub const SNAP_APERTURE: f32 = 10.;
+/// Screen size in pixels of the marker shown on the snapped point.
+pub const SNAP_MARKER_SIZE: f32 = 6.;
+
+const SNAP_COLOR: Color = Color::srgb(1., 0.9, 0.);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapKind {
+    Endpoint,
+    Midpoint,
+    Center,
+    Quadrant,
+    Node,
+}
+
+/// Which kinds of points the cursor snaps onto.
+#[derive(Resource, Debug, PartialEq)]
+pub struct SnapModes {
+    pub enabled: bool,
+    pub endpoint: bool,
+    pub midpoint: bool,
+    pub center: bool,
+    pub quadrant: bool,
+    pub node: bool,
+}
+
+impl Default for SnapModes {
+    fn default() -> Self {
+        SnapModes {
+            enabled: true,
+            endpoint: true,
+            midpoint: true,
+            center: true,
+            quadrant: true,
+            node: true,
+        }
+    }
+}
+
+impl SnapModes {
+    fn runs(&self, kind: SnapKind) -> bool {
+        self.enabled
+            && match kind {
+                SnapKind::Endpoint => self.endpoint,
+                SnapKind::Midpoint => self.midpoint,
+                SnapKind::Center => self.center,
+                SnapKind::Quadrant => self.quadrant,
+                SnapKind::Node => self.node,
+            }
+    }
+}
+
+/// Shapes offering points to snap onto.
+#[derive(SystemParam)]
+pub struct SnapTargets<'w, 's> {
+    modes: Res<'w, SnapModes>,
+    dots: Query<'w, 's, &'static Dot>,
+    lines: Query<'w, 's, &'static Line>,
+    rectangles: Query<'w, 's, &'static Rectangle>,
+    circles: Query<'w, 's, &'static Circle>,
+    arcs: Query<'w, 's, &'static Arc>,
+}
+
+impl SnapTargets<'_, '_> {
+    /// Closest point of a running mode within `aperture` of `point`.
+    pub fn snap(&self, point: Vec3, aperture: f32) -> Option<(Vec3, SnapKind)> {
+        self.candidates()
+            .into_iter()
+            .filter(|(_, kind)| self.modes.runs(*kind))
+            .map(|(candidate, kind)| (candidate.distance(point), candidate, kind))
+            .filter(|(distance, _, _)| *distance <= aperture)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, candidate, kind)| (candidate, kind))
+    }
+
+    fn candidates(&self) -> Vec<(Vec3, SnapKind)> {
+        let mut candidates = Vec::new();
+        for line in self.lines.iter() {
+            candidates.push((line.start, SnapKind::Endpoint));
+            candidates.push((line.end, SnapKind::Endpoint));
+            candidates.push((line.start.midpoint(line.end), SnapKind::Midpoint));
+        }
+        for rectangle in self.rectangles.iter() {
+            let corners = rectangle.corners();
+            for (index, corner) in corners.iter().enumerate() {
+                candidates.push((*corner, SnapKind::Endpoint));
+                candidates.push((
+                    corner.midpoint(corners[(index + 1) % corners.len()]),
+                    SnapKind::Midpoint,
+                ));
+            }
+            candidates.push((rectangle.start.midpoint(rectangle.end), SnapKind::Center));
+        }
+        for circle in self.circles.iter() {
+            candidates.push((circle.center, SnapKind::Center));
+            candidates.extend(
+                quadrants(circle.center, circle.radius).map(|point| (point, SnapKind::Quadrant)),
+            );
+        }
+        for arc in self.arcs.iter() {
+            candidates.push((arc.start, SnapKind::Endpoint));
+            candidates.push((arc.end, SnapKind::Endpoint));
+            candidates.push((
+                arc.center + Quat::from_rotation_y(arc.sweep / 2.) * (arc.start - arc.center),
+                SnapKind::Midpoint,
+            ));
+            candidates.push((arc.center, SnapKind::Center));
+            candidates.extend(
+                quadrants(arc.center, arc.radius())
+                    .filter(|point| arc.contains_angle(*point))
+                    .map(|point| (point, SnapKind::Quadrant)),
+            );
+        }
+        // Last, so a shape's own point wins over the Dot marking it
+        for dot in self.dots.iter() {
+            candidates.push((dot.position, SnapKind::Node));
+        }
+        candidates
+    }
+}
+
+pub struct SnapPlugin;
+
+impl Plugin for SnapPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SnapModes::default())
+            .add_systems(Update, (change_snap_modes, display_snap_marker));
+    }
+}
+
+/// World distance covered by one pixel of the viewport around `position`.
+pub fn world_per_pixel(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    position: Vec2,
+) -> Option<f32> {
+    let at = camera.viewport_to_world(camera_transform, position).ok()?;
+    let next = camera
+        .viewport_to_world(camera_transform, position + Vec2::X)
+        .ok()?;
+    Some(at.origin.distance(next.origin))
+}
+
+/// F6 turns object snaps on or off; F1 to F5 toggle endpoint, midpoint, center, quadrant
+/// and node snaps.
+#[hot]
+fn change_snap_modes(keyboard: Res<ButtonInput<KeyCode>>, mut modes: ResMut<SnapModes>) {
+    if keyboard.just_pressed(KeyCode::F6) {
+        modes.enabled = !modes.enabled;
+    } else if keyboard.just_pressed(KeyCode::F1) {
+        modes.endpoint = !modes.endpoint;
+    } else if keyboard.just_pressed(KeyCode::F2) {
+        modes.midpoint = !modes.midpoint;
+    } else if keyboard.just_pressed(KeyCode::F3) {
+        modes.center = !modes.center;
+    } else if keyboard.just_pressed(KeyCode::F4) {
+        modes.quadrant = !modes.quadrant;
+    } else if keyboard.just_pressed(KeyCode::F5) {
+        modes.node = !modes.node;
+    } else {
+        return;
+    }
+    info!("Snaps: {:?}", *modes);
+}
+
+#[hot]
+fn display_snap_marker(
+    mut gizmos: Gizmos,
+    cursor: Res<Cursor>,
+    camera_query: Single<(&Camera, &GlobalTransform)>,
+) {
+    let Some(kind) = cursor.snap else {
+        return;
+    };
+    let (camera, camera_transform) = *camera_query;
+    let Some(size) = camera
+        .world_to_viewport(camera_transform, cursor.position)
+        .ok()
+        .and_then(|position| world_per_pixel(camera, camera_transform, position))
+        .map(|pixel| pixel * SNAP_MARKER_SIZE)
+    else {
+        return;
+    };
+
+    // Each kind gets its own glyph, drawn slightly above the plane over the shapes
+    let center = cursor.position + Vec3::Y * 1e-3;
+    let flat = Quat::from_rotation_arc(Vec3::Z, Vec3::Y);
+    let outline = |corners: &[Vec3]| {
+        corners
+            .iter()
+            .chain(corners.first())
+            .map(|corner| center + *corner * size)
+            .collect::<Vec<_>>()
+    };
+    match kind {
+        SnapKind::Endpoint => gizmos.linestrip(
+            outline(&[
+                vec3(-1., 0., -1.),
+                vec3(1., 0., -1.),
+                vec3(1., 0., 1.),
+                vec3(-1., 0., 1.),
+            ]),
+            SNAP_COLOR,
+        ),
+        SnapKind::Midpoint => gizmos.linestrip(
+            outline(&[vec3(0., 0., -1.), vec3(1., 0., 1.), vec3(-1., 0., 1.)]),
+            SNAP_COLOR,
+        ),
+        SnapKind::Center => {
+            gizmos.circle(Isometry3d::new(center, flat), size, SNAP_COLOR);
+        }
+        SnapKind::Quadrant => gizmos.linestrip(
+            outline(&[
+                vec3(0., 0., -1.),
+                vec3(1., 0., 0.),
+                vec3(0., 0., 1.),
+                vec3(-1., 0., 0.),
+            ]),
+            SNAP_COLOR,
+        ),
+        SnapKind::Node => {
+            gizmos.circle(Isometry3d::new(center, flat), size, SNAP_COLOR);
+            gizmos.line(
+                center + vec3(-1., 0., -1.) * size,
+                center + vec3(1., 0., 1.) * size,
+                SNAP_COLOR,
+            );
+            gizmos.line(
+                center + vec3(-1., 0., 1.) * size,
+                center + vec3(1., 0., -1.) * size,
+                SNAP_COLOR,
+            );
+        }
+    }
+}
+
+/// Points of the circle around `center` straight along +X, -Z, -X and +Z.
+fn quadrants(center: Vec3, radius: f32) -> impl Iterator<Item = Vec3> {
+    (0..4).map(move |index| {
+        center + Quat::from_rotation_y(FRAC_PI_2 * index as f32) * Vec3::X * radius
+    })
+}